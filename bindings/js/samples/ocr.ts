@@ -63,7 +63,7 @@ const iPicker = DynWinRtType.registerInterface("IFileOpenPicker", IID_IFileOpenP
                 IID_IPickFileResult))))
 
 const iPickResult = DynWinRtType.registerInterface("IPickFileResult", IID_IPickFileResult)
-    .addMethod("get_File", new DynWinRtMethodSig().addOut(DynWinRtType.hstring()))
+    .addMethod("get_Path", new DynWinRtMethodSig().addOut(DynWinRtType.hstring()))
 
 // StorageFile
 const iStorageFileStatics = DynWinRtType.registerInterface("IStorageFileStatics", IID_IStorageFileStatics)
@@ -135,7 +135,7 @@ async function main() {
     const picker = iPickerFactory.methodByName("CreateWithMode").invoke(pickerFactory, [DynWinRtValue.i64(0)])
     const asyncPickOp = iPicker.methodByName("PickSingleFileAsync").invoke(picker, [])
     const pickResult = await asyncPickOp.toPromise()
-    const filePath = iPickResult.methodByName("get_File").invoke(pickResult, [])
+    const filePath = iPickResult.methodByName("get_Path").invoke(pickResult, [])
     console.log('Selected file:', filePath.toString())
 
     // --- Load bitmap from file path ---
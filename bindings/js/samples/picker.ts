@@ -34,10 +34,10 @@ const iPicker = DynWinRtType.registerInterface("IFileOpenPicker", iPickerIid)
             )
         ))) // 13
 
-// IPickFileResult
+// IPickFileResult (only exposes Path)
 const iPickResultIid = WinGuid.parse("E6F2E3D6-7BB0-5D81-9E7D-6FD35A1F25AB")
 const iPickResult = DynWinRtType.registerInterface("IPickFileResult", iPickResultIid)
-    .addMethod("get_File", new DynWinRtMethodSig().addOut(DynWinRtType.hstring()))  // 6
+    .addMethod("get_Path", new DynWinRtMethodSig().addOut(DynWinRtType.hstring()))  // 6
 
 // ======================================================================
 // Use
@@ -57,7 +57,7 @@ async function main() {
     const pickedFile = await asyncOp.toPromise()
 
     // by name
-    const path = iPickResult.methodByName("get_File").invoke(pickedFile, [])
+    const path = iPickResult.methodByName("get_Path").invoke(pickedFile, [])
     console.log("Selected Path", path.toString())
 }
 main();
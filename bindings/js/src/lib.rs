@@ -184,7 +184,7 @@ impl DynWinRTType {
   /// Look up an enum member's i32 value by name.
   #[napi]
   pub fn get_enum_value(enum_name: String, member_name: String) -> Option<i32> {
-    TABLE.get_enum_value(&enum_name, &member_name)
+    TABLE.get_enum_value(&enum_name, &member_name).map(|v| v as i32)
   }
 
   /// Declare a parameterized type (generic instantiation, e.g. IReference<UInt64>).
@@ -459,14 +459,14 @@ impl DynWinRTValue {
   /// Create an enum value from an i32. The type_handle must be an enum type.
   #[napi]
   pub fn enum_value(enum_type: &DynWinRTType, value: i32) -> DynWinRTValue {
-    DynWinRTValue(dynwinrt::WinRTValue::Enum { value, type_handle: enum_type.0.clone() })
+    DynWinRTValue(dynwinrt::WinRTValue::Enum { value: value as i64, type_handle: enum_type.0.clone() })
   }
 
   /// Get the i32 value of an enum. Returns None if not an enum.
   #[napi]
   pub fn get_enum_int(&self) -> Option<i32> {
     match &self.0 {
-      dynwinrt::WinRTValue::Enum { value, .. } => Some(*value),
+      dynwinrt::WinRTValue::Enum { value, .. } => Some(*value as i32),
       _ => None,
     }
   }
@@ -594,11 +594,13 @@ impl DynWinRTValue {
       dynwinrt::WinRTValue::I8(i) => *i as i32,
       dynwinrt::WinRTValue::U8(i) => *i as i32,
       dynwinrt::WinRTValue::I16(i) => *i as i32,
-      dynwinrt::WinRTValue::U16(i) => *i as i32,
+      dynwinrt::WinRTValue::U16(i) | dynwinrt::WinRTValue::Char16(i) => *i as i32,
       dynwinrt::WinRTValue::I32(i) => *i,
       dynwinrt::WinRTValue::U32(i) => *i as i32,
       dynwinrt::WinRTValue::HResult(hr) => hr.0,
-      dynwinrt::WinRTValue::Enum { value, .. } => *value,
+      dynwinrt::WinRTValue::Enum { value, .. } => *value as i32,
+      // Void results read as 0, as they did when they were HResult(0)
+      dynwinrt::WinRTValue::Unit => 0,
       _ => panic!("Cannot convert {:?} to number", self.0.get_type_kind()),
     }
   }
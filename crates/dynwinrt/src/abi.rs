@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AbiType {
     Bool,
     I8,
@@ -12,6 +12,9 @@ pub enum AbiType {
     F32,
     F64,
     Ptr,
+    /// Value-type struct passed by value: field ABI types in declaration
+    /// order, nested structs inline. Laid out with C rules, like `MetadataTable`.
+    Struct(Vec<AbiType>),
 }
 
 impl AbiType {
@@ -29,9 +32,11 @@ impl AbiType {
             AbiType::F32 => AbiValue::F32(0.0),
             AbiType::F64 => AbiValue::F64(0.0),
             AbiType::Ptr => AbiValue::Pointer(std::ptr::null_mut()),
+            AbiType::Struct(_) => panic!("Struct values have no AbiValue; use ValueTypeData"),
         }
     }
 
+    #[cfg(feature = "dynamic-call")]
     pub fn libffi_type(&self) -> libffi::middle::Type {
         match self {
             AbiType::Bool | AbiType::U8 => libffi::middle::Type::u8(),
@@ -45,6 +50,9 @@ impl AbiType {
             AbiType::F32 => libffi::middle::Type::f32(),
             AbiType::F64 => libffi::middle::Type::f64(),
             AbiType::Ptr => libffi::middle::Type::pointer(),
+            AbiType::Struct(fields) => {
+                libffi::middle::Type::structure(fields.iter().map(AbiType::libffi_type))
+            }
         }
     }
 }
@@ -66,6 +74,7 @@ pub enum AbiValue {
 }
 
 impl AbiValue {
+    #[cfg(feature = "dynamic-call")]
     pub fn as_out_ptr(&self) -> *const std::ffi::c_void {
         match self {
             AbiValue::Bool(v) => std::ptr::from_ref(v).cast(),
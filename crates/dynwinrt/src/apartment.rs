@@ -0,0 +1,170 @@
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::ThreadId;
+
+use windows::Win32::System::Com::{APTTYPE, APTTYPE_MTA, APTTYPEQUALIFIER, CoGetApartmentType, CoGetContextToken};
+
+use crate::value::WinRTValue;
+
+static OFF_APARTMENT_RELEASES: AtomicUsize = AtomicUsize::new(0);
+
+/// A non-agile `ApartmentBound` value dropped outside the apartment it was
+/// wrapped in, reported to the release observer.
+#[derive(Debug, Clone, Copy)]
+pub struct ForeignRelease {
+    pub created_on: ThreadId,
+    pub released_on: ThreadId,
+}
+
+pub type ReleaseObserver = Box<dyn Fn(ForeignRelease) + Send + Sync>;
+
+static RELEASE_OBSERVER: RwLock<Option<ReleaseObserver>> = RwLock::new(None);
+/// Fast-path flag so drops skip the lock entirely when no observer is set.
+static HAS_RELEASE_OBSERVER: AtomicBool = AtomicBool::new(false);
+
+/// Register a process-wide observer for foreign releases, replacing any previous one.
+pub fn set_release_observer(observer: ReleaseObserver) {
+    *RELEASE_OBSERVER.write().unwrap() = Some(observer);
+    HAS_RELEASE_OBSERVER.store(true, Ordering::Release);
+}
+
+/// Remove the release observer (restores the default no-op).
+pub fn clear_release_observer() {
+    HAS_RELEASE_OBSERVER.store(false, Ordering::Release);
+    *RELEASE_OBSERVER.write().unwrap() = None;
+}
+
+/// The calling thread's apartment: one shared key for the MTA (implicit
+/// members included), the context token for anything else. `None` when COM
+/// is not initialized and no MTA exists.
+fn current_apartment() -> Option<usize> {
+    let mut apt_type = APTTYPE::default();
+    let mut qualifier = APTTYPEQUALIFIER::default();
+    unsafe { CoGetApartmentType(&mut apt_type, &mut qualifier) }.ok()?;
+    if apt_type == APTTYPE_MTA {
+        return Some(0);
+    }
+    unsafe { CoGetContextToken() }.ok()
+}
+
+/// An object value that remembers the apartment it was wrapped in.
+///
+/// A non-agile object must be released in its own apartment; dropping one
+/// elsewhere can deadlock or corrupt the apartment's state. This does not
+/// marshal the release back, but reports it: the drop is counted in
+/// `off_apartment_releases` and passed to the observer set with
+/// `set_release_observer`. To use such an object elsewhere, pass an
+/// `AgileReference` instead.
+pub struct ApartmentBound {
+    value: WinRTValue,
+    origin: ThreadId,
+    apartment: Option<usize>,
+    agile: bool,
+}
+
+impl ApartmentBound {
+    /// Wrap `value`, checking agility with a QI for `IAgileObject`.
+    /// Non-object values count as agile.
+    pub fn new(value: WinRTValue) -> Self {
+        let agile = value.as_object().is_none()
+            || value.cast(&windows_core::imp::IAgileObject::IID).is_ok();
+        ApartmentBound {
+            value,
+            origin: std::thread::current().id(),
+            apartment: current_apartment(),
+            agile,
+        }
+    }
+
+    pub fn value(&self) -> &WinRTValue {
+        &self.value
+    }
+
+    pub fn is_agile(&self) -> bool {
+        self.agile
+    }
+
+    /// True when the current thread is outside the apartment that wrapped
+    /// the value. Other threads of the same MTA are not foreign.
+    pub fn is_foreign_apartment(&self) -> bool {
+        current_apartment() != self.apartment
+    }
+}
+
+impl Drop for ApartmentBound {
+    fn drop(&mut self) {
+        if !self.agile && self.is_foreign_apartment() {
+            OFF_APARTMENT_RELEASES.fetch_add(1, Ordering::Relaxed);
+            if !HAS_RELEASE_OBSERVER.load(Ordering::Acquire) {
+                return;
+            }
+            if let Some(observer) = RELEASE_OBSERVER.read().unwrap().as_ref() {
+                observer(ForeignRelease { created_on: self.origin, released_on: std::thread::current().id() });
+            }
+        }
+    }
+}
+
+/// How many non-agile `ApartmentBound` values were dropped outside their apartment.
+pub fn off_apartment_releases() -> usize {
+    OFF_APARTMENT_RELEASES.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use windows::Win32::System::WinRT::{RO_INIT_SINGLETHREADED, RoInitialize, RoUninitialize};
+
+    use super::*;
+    use crate::mock::MockObject;
+
+    #[test]
+    fn foreign_apartment_drop_reports_only_non_agile_objects() {
+        let _ = crate::ensure_mta();
+        let agile = MockObject::new(&[]);
+        let non_agile = MockObject::new_non_agile(&[]);
+        let before = off_apartment_releases();
+
+        // An STA thread is a different apartment from the MTA
+        let on_sta = |bound: ApartmentBound| {
+            std::thread::spawn(move || {
+                unsafe { RoInitialize(RO_INIT_SINGLETHREADED) }.unwrap();
+                assert!(bound.is_foreign_apartment());
+                drop(bound);
+                unsafe { RoUninitialize() };
+            })
+            .join()
+            .unwrap();
+        };
+
+        let bound = ApartmentBound::new(agile.value());
+        assert!(bound.is_agile());
+        on_sta(bound);
+        assert_eq!(off_apartment_releases(), before);
+
+        // Another MTA thread shares the apartment
+        let bound = ApartmentBound::new(non_agile.value());
+        assert!(!bound.is_agile());
+        std::thread::spawn(move || {
+            let _ = crate::ensure_mta();
+            assert!(!bound.is_foreign_apartment());
+            drop(bound);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(off_apartment_releases(), before);
+
+        static REPORTS: Mutex<Vec<ForeignRelease>> = Mutex::new(Vec::new());
+        set_release_observer(Box::new(|release| REPORTS.lock().unwrap().push(release)));
+        on_sta(ApartmentBound::new(non_agile.value()));
+        clear_release_observer();
+        assert_eq!(off_apartment_releases(), before + 1);
+        let reports = REPORTS.lock().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].created_on, std::thread::current().id());
+        assert_ne!(reports[0].released_on, reports[0].created_on);
+
+        assert_eq!((agile.refs(), non_agile.refs()), (0, 0));
+    }
+}
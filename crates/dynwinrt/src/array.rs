@@ -76,6 +76,22 @@ impl ArrayData {
         }
     }
 
+    /// Take ownership of a ReceiveArray out-param pair `(UINT32* count, T** data)`.
+    /// A null `data` is never freed; a non-null buffer is freed on drop even
+    /// when `count == 0`.
+    pub(crate) fn from_receive_array(element_type: TypeHandle, data_ptr: *mut c_void, len: u32) -> Self {
+        if data_ptr.is_null() {
+            ArrayData::empty(element_type)
+        } else {
+            ArrayData::from_cotaskmem(element_type, data_ptr, len as usize)
+        }
+    }
+
+    /// Copy every element into an owned `Vec<WinRTValue>` (AddRef / duplicate as needed).
+    pub fn to_values(&self) -> Vec<WinRTValue> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+
     pub fn len(&self) -> usize {
         match &self.buffer {
             ArrayBuffer::Values(v) => v.len(),
@@ -145,17 +161,17 @@ impl ArrayData {
                 TypeKind::I16 => {
                     WinRTValue::I16(*(base.add(index * elem_size) as *const i16))
                 }
-                TypeKind::U16 | TypeKind::Char16 => {
+                TypeKind::U16 => {
                     WinRTValue::U16(*(base.add(index * elem_size) as *const u16))
                 }
+                TypeKind::Char16 => {
+                    WinRTValue::Char16(*(base.add(index * elem_size) as *const u16))
+                }
                 TypeKind::I32 => {
                     WinRTValue::I32(*(base.add(index * elem_size) as *const i32))
                 }
                 TypeKind::Enum(_) => {
-                    WinRTValue::Enum {
-                        value: *(base.add(index * elem_size) as *const i32),
-                        type_handle: self.element_type.clone(),
-                    }
+                    self.element_type.read_enum(base.add(index * elem_size))
                 }
                 TypeKind::U32 => {
                     WinRTValue::U32(*(base.add(index * elem_size) as *const u32))
@@ -380,9 +396,9 @@ fn serialize_to_buffer(element_type: &TypeHandle, values: &[WinRTValue]) -> Vec<
             WinRTValue::I8(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
             WinRTValue::U8(v) => buffer.push(*v),
             WinRTValue::I16(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
-            WinRTValue::U16(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
+            WinRTValue::U16(v) | WinRTValue::Char16(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
             WinRTValue::I32(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
-            WinRTValue::Enum { value, .. } => buffer.extend_from_slice(&value.to_ne_bytes()),
+            WinRTValue::Enum { value, .. } => buffer.extend_from_slice(&value.to_ne_bytes()[..elem_size]),
             WinRTValue::U32(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
             WinRTValue::I64(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
             WinRTValue::U64(v) => buffer.extend_from_slice(&v.to_ne_bytes()),
@@ -0,0 +1,129 @@
+use std::ffi::c_void;
+
+use windows_core::{GUID, HRESULT, Interface};
+
+use crate::com_helpers::E_BOUNDS;
+use crate::result::{Error, Result};
+use crate::value::{IBUFFER, WinRTValue};
+
+/// IBufferByteAccess (IUnknown-based): Buffer(byte**) at vtable index 3.
+const IBUFFER_BYTE_ACCESS: GUID = GUID::from_u128(0x905a0fef_bc53_11df_8c49_001e4fc686da);
+
+/// A `Windows.Storage.Streams.IBuffer` with direct access to its bytes.
+///
+/// Reads are limited to `length()`; writes may go up to `capacity()` and
+/// grow the length to cover what was written.
+pub struct DynBuffer {
+    buffer: WinRTValue,
+}
+
+impl DynBuffer {
+    /// QI `value` for IBuffer.
+    pub fn new(value: &WinRTValue) -> Result<Self> {
+        Ok(DynBuffer { buffer: value.cast(&IBUFFER)? })
+    }
+
+    /// The underlying IBuffer object, for passing to other APIs.
+    pub fn as_value(&self) -> &WinRTValue {
+        &self.buffer
+    }
+
+    pub fn capacity(&self) -> Result<u32> {
+        self.buffer.buffer_capacity()
+    }
+
+    pub fn length(&self) -> Result<u32> {
+        self.buffer.buffer_length()
+    }
+
+    pub fn set_length(&self, len: u32) -> Result<()> {
+        self.buffer.buffer_set_length(len)
+    }
+
+    /// The valid bytes, `0..length()`.
+    pub fn as_bytes(&self) -> Result<&[u8]> {
+        let len = self.length()? as usize;
+        Ok(unsafe { std::slice::from_raw_parts(self.data()?, len) })
+    }
+
+    /// `count` bytes starting at `offset`; fails with E_BOUNDS past `length()`.
+    pub fn read(&self, offset: usize, count: usize) -> Result<&[u8]> {
+        let bytes = self.as_bytes()?;
+        offset.checked_add(count)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or_else(|| bounds_error(offset, count, bytes.len()))
+    }
+
+    /// Copy `data` in at `offset`, extending the length if needed. Fails with
+    /// E_BOUNDS past `capacity()`.
+    pub fn write(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        let capacity = self.capacity()? as usize;
+        let end = offset.checked_add(data.len())
+            .filter(|end| *end <= capacity)
+            .ok_or_else(|| bounds_error(offset, data.len(), capacity))?;
+        unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), self.data()?.add(offset), data.len()) };
+        if end > self.length()? as usize {
+            self.set_length(end as u32)?;
+        }
+        Ok(())
+    }
+
+    /// `IBufferByteAccess::Buffer`: start of the backing storage, valid while
+    /// the buffer is alive.
+    fn data(&self) -> Result<*mut u8> {
+        let access = self.buffer.cast(&IBUFFER_BYTE_ACCESS)?;
+        let obj = access.as_object()
+            .ok_or_else(|| Error::ExpectObjectTypeError(access.get_type_kind()))?;
+        let mut data: *mut u8 = std::ptr::null_mut();
+        unsafe {
+            let vtable = *(obj.as_raw() as *const *const usize);
+            let buffer: unsafe extern "system" fn(*mut c_void, *mut *mut u8) -> HRESULT =
+                std::mem::transmute(*vtable.add(3));
+            buffer(obj.as_raw(), &mut data).ok()?;
+        }
+        Ok(data)
+    }
+}
+
+fn bounds_error(offset: usize, count: usize, limit: usize) -> Error {
+    windows_core::Error::new(E_BOUNDS, format!("bytes {}..{}+{} exceed {}", offset, offset, count, limit)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use windows::Security::Cryptography::CryptographicBuffer;
+    use windows::Storage::Streams::Buffer;
+    use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+    use windows_core::IUnknown;
+
+    use super::*;
+
+    #[test]
+    fn read_cryptographic_buffer_bytes() {
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let buffer = CryptographicBuffer::CreateFromByteArray(&[1, 2, 3, 4, 5]).unwrap();
+        let dyn_buffer = DynBuffer::new(&WinRTValue::Object(buffer.cast::<IUnknown>().unwrap())).unwrap();
+        assert_eq!(dyn_buffer.length().unwrap(), 5);
+        assert_eq!(dyn_buffer.as_bytes().unwrap(), &[1, 2, 3, 4, 5]);
+        assert_eq!(dyn_buffer.read(1, 3).unwrap(), &[2, 3, 4]);
+
+        // Past length is rejected even when capacity would allow it
+        assert!(dyn_buffer.read(3, 3).is_err());
+        assert!(dyn_buffer.read(usize::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn write_extends_length_within_capacity() {
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let buffer = Buffer::Create(8).unwrap();
+        let mut dyn_buffer = DynBuffer::new(&WinRTValue::Object(buffer.cast::<IUnknown>().unwrap())).unwrap();
+        dyn_buffer.write(2, &[7, 8]).unwrap();
+        assert_eq!(buffer.Length().unwrap(), 4);
+        assert_eq!(dyn_buffer.read(2, 2).unwrap(), &[7, 8]);
+
+        assert!(dyn_buffer.write(7, &[1, 2]).is_err());
+        assert_eq!(buffer.Length().unwrap(), 4);
+    }
+}
@@ -1,8 +1,60 @@
 use core::ffi::c_void;
+#[cfg(feature = "dynamic-call")]
 use libffi::middle::{Arg, arg};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use windows_core::{HRESULT, Interface};
 
-use crate::{abi::AbiValue, signature::Parameter, value::WinRTValue};
+use crate::value::WinRTValue;
+#[cfg(feature = "dynamic-call")]
+use crate::{abi::AbiValue, signature::Parameter};
+
+// ---------------------------------------------------------------------------
+// Call observer — lets embedders route dynamic call outcomes to log/tracing
+// ---------------------------------------------------------------------------
+
+/// Outcome of a single dynamic method call, reported to the call observer.
+///
+/// Reported for `Method::call_dynamic` (and everything built on it), the
+/// `Method::call_getter_*` fast paths, `WinRTValue::call_batch` and the
+/// `WinRTValue` helpers that call a fixed slot (`to_winrt_string`, `close`,
+/// `trust_level`, the buffer and vector-changed helpers, ...). The
+/// `SetCompleted`/`GetResults` calls made while awaiting and the
+/// `diagnostics` probes are not reported.
+#[derive(Debug, Clone, Copy)]
+pub struct CallEvent {
+    pub method_index: usize,
+    pub arg_count: usize,
+    pub hresult: HRESULT,
+}
+
+pub type CallObserver = Box<dyn Fn(CallEvent) + Send + Sync>;
+
+static CALL_OBSERVER: RwLock<Option<CallObserver>> = RwLock::new(None);
+/// Fast-path flag so calls skip the lock entirely when no observer is set.
+static HAS_CALL_OBSERVER: AtomicBool = AtomicBool::new(false);
+
+/// Register a process-wide observer for dynamic calls, replacing any previous one.
+pub fn set_call_observer(observer: CallObserver) {
+    *CALL_OBSERVER.write().unwrap() = Some(observer);
+    HAS_CALL_OBSERVER.store(true, Ordering::Release);
+}
+
+/// Remove the call observer (restores the default no-op).
+pub fn clear_call_observer() {
+    HAS_CALL_OBSERVER.store(false, Ordering::Release);
+    *CALL_OBSERVER.write().unwrap() = None;
+}
+
+#[inline]
+pub(crate) fn notify_call(method_index: usize, arg_count: usize, hresult: HRESULT) {
+    if !HAS_CALL_OBSERVER.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some(observer) = CALL_OBSERVER.read().unwrap().as_ref() {
+        observer(CallEvent { method_index, arg_count, hresult });
+    }
+}
 
 pub fn get_vtable_function_ptr(obj: *mut c_void, method_index: usize) -> *mut c_void {
     unsafe {
@@ -43,18 +95,43 @@ pub fn call_winrt_method_2<T1, T2>(
     }
 }
 
+pub fn call_winrt_method_3<T1, T2, T3>(
+    vtable_index: usize,
+    obj: *mut c_void,
+    x1: T1,
+    x2: T2,
+    x3: T3,
+) -> HRESULT {
+    let method_ptr = get_vtable_function_ptr(obj, vtable_index);
+    unsafe {
+        let method: extern "system" fn(*mut c_void, T1, T2, T3) -> HRESULT =
+            std::mem::transmute(method_ptr);
+        method(obj, x1, x2, x3)
+    }
+}
+
 /// Dispatch a scalar WinRTValue through a closure that receives the raw ABI value.
 /// Used by direct call helpers to avoid repeating the same 14-branch match.
 macro_rules! dispatch_scalar {
     ($in_val:expr, $call:expr) => {
         match $in_val {
-            WinRTValue::Bool(v) => $call(*v),
+            // WinRT Boolean is one byte on the ABI: pass 0/1, never a widened int
+            WinRTValue::Bool(v) => $call(*v as u8),
             WinRTValue::I8(v) => $call(*v),
             WinRTValue::U8(v) => $call(*v),
             WinRTValue::I16(v) => $call(*v),
-            WinRTValue::U16(v) => $call(*v),
+            WinRTValue::U16(v) | WinRTValue::Char16(v) => $call(*v),
             WinRTValue::I32(v) => $call(*v),
-            WinRTValue::Enum { value: v, .. } => $call(*v),
+            // Pass enums at their underlying width, like any other small integer
+            WinRTValue::Enum { value: v, type_handle } => match type_handle.enum_underlying().kind() {
+                TypeKind::I8 => $call(*v as i8),
+                TypeKind::U8 => $call(*v as u8),
+                TypeKind::I16 => $call(*v as i16),
+                TypeKind::U16 => $call(*v as u16),
+                TypeKind::I32 => $call(*v as i32),
+                TypeKind::U32 => $call(*v as u32),
+                _ => $call(*v),
+            },
             WinRTValue::U32(v) => $call(*v),
             WinRTValue::I64(v) => $call(*v),
             WinRTValue::U64(v) => $call(*v),
@@ -63,6 +140,7 @@ macro_rules! dispatch_scalar {
             WinRTValue::Object(o) => $call(o.as_raw()),
             WinRTValue::Null => $call(std::ptr::null_mut::<c_void>()),
             WinRTValue::Guid(g) => $call(*g),
+            WinRTValue::OutValue(slot, _) => $call(slot.as_ptr()),
             _ => panic!("dispatch_scalar: unsupported type {:?}", $in_val),
         }
     };
@@ -96,10 +174,13 @@ pub fn call_fill_array_1in(
     })
 }
 
-use crate::metadata_table::{TypeHandle, TypeKind};
+use crate::metadata_table::TypeKind;
+#[cfg(feature = "dynamic-call")]
+use crate::metadata_table::TypeHandle;
 
 /// Stable heap storage for array in-param data.
 /// Owns the serialized byte buffer so it stays alive for the FFI call.
+#[cfg(feature = "dynamic-call")]
 struct ArrayInSlot {
     length: u32,
     data_ptr: *const u8,
@@ -107,6 +188,7 @@ struct ArrayInSlot {
 }
 
 /// Stable heap storage for array out-param data (callee writes into these fields).
+#[cfg(feature = "dynamic-call")]
 struct ArrayOutSlot {
     length: u32,
     data_ptr: *mut c_void,
@@ -114,6 +196,7 @@ struct ArrayOutSlot {
 }
 
 /// Stable heap storage for FillArray out-param data (caller-allocated via CoTaskMemAlloc).
+#[cfg(feature = "dynamic-call")]
 struct FillArraySlot {
     capacity: u32,
     buffer_ptr: *mut u8, // CoTaskMemAlloc'd
@@ -121,6 +204,7 @@ struct FillArraySlot {
     element_type: TypeHandle,
 }
 
+#[cfg(feature = "dynamic-call")]
 impl Drop for FillArraySlot {
     fn drop(&mut self) {
         // Free the buffer if ownership was not transferred to ArrayData
@@ -132,6 +216,22 @@ impl Drop for FillArraySlot {
     }
 }
 
+/// E_INVALIDARG for an argument that does not fit its parameter.
+#[cfg(feature = "dynamic-call")]
+fn invalid_arg(message: String) -> windows_core::Error {
+    windows_core::Error::new(HRESULT(0x80070057u32 as i32), message) // E_INVALIDARG
+}
+
+/// Whether the target ABI passes a struct argument of `size` bytes as a
+/// pointer to a caller-owned copy. On x64 Windows every struct that is not
+/// 1, 2, 4 or 8 bytes is (e.g. the 24-byte `BasicGeoposition`); elsewhere
+/// libffi's by-value struct passing is used as is.
+pub(crate) fn passes_struct_indirectly(size: usize) -> bool {
+    cfg!(all(windows, target_arch = "x86_64")) && !matches!(size, 1 | 2 | 4 | 8)
+}
+
+/// General-case dispatch through libffi, for shapes without a direct path.
+#[cfg(feature = "dynamic-call")]
 pub fn call_winrt_method_dynamic(
     vtable_index: usize,
     obj: *mut c_void,
@@ -160,6 +260,17 @@ pub fn call_winrt_method_dynamic(
     // Array in-param storage: pre-compute all before building ffi_args
     let mut array_in_slots: Vec<Box<ArrayInSlot>> = Vec::new();
 
+    // Copies of `by_pointer` struct ins: the callee may write to its copy
+    let mut struct_in_copies: Vec<ValueTypeData> = Vec::new();
+    let mut struct_in_ptrs: Vec<*const u8> = Vec::new();
+
+    // In-out storage: arrays copied into CoTaskMem buffers (handed to ArrayData
+    // afterwards), scalars and structs into typed out buffers seeded from
+    // their arguments
+    let mut in_out_arrays: Vec<Box<FillArraySlot>> = Vec::new();
+    let mut in_out_buffers: Vec<WinRTValue> = Vec::new();
+    let mut in_out_ptrs: Vec<*mut c_void> = Vec::new();
+
     // FillArray storage: caller-allocated buffers
     let mut fill_array_slots: Vec<Box<FillArraySlot>> = Vec::new();
     let mut fill_array_map: Vec<Option<usize>> = Vec::with_capacity(out_count);
@@ -218,6 +329,11 @@ pub fn call_winrt_method_dynamic(
                 struct_out_values.push(None);
                 fill_array_map.push(None);
             } else if matches!(p.typ.kind(), TypeKind::Struct(_)) {
+                // Struct results of any size (e.g. 16-byte Rect) are not returned
+                // by value: the HRESULT is the native return, and WinRT lowers the
+                // metadata return to a trailing `T*` param into caller storage.
+                // So there is no hidden first argument to account for, only a
+                // pointer to a zeroed buffer of the struct's layout.
                 let val = p.typ.default_value();
                 out_ptrs.push(val.as_ptr() as *const std::ffi::c_void);
                 out_values.push(AbiValue::Pointer(std::ptr::null_mut()));
@@ -236,7 +352,46 @@ pub fn call_winrt_method_dynamic(
 
     // Phase 1b: Pre-compute all array in-param data (must happen before Phase 2)
     for p in parameters {
-        if !p.is_out() && p.typ.is_array() {
+        if p.is_in_out() {
+            let element = if p.typ.is_array() { p.typ.array_element_type() } else { p.typ.clone() };
+            if !element.kind().is_blittable() && !matches!(element.kind(), crate::metadata_table::TypeKind::Enum(_)) {
+                return Err(invalid_arg(format!("in-out parameters must be blittable, got {:?}", element.kind())));
+            }
+            let arg = &args[p.value_index];
+            if p.typ.is_array() {
+                let array_data = arg.as_array().ok_or_else(|| {
+                    invalid_arg(format!("in-out array parameter needs an Array argument, got {:?}", arg.get_type_kind()))
+                })?;
+                let bytes = array_data.serialize_for_abi();
+                let buffer_ptr = unsafe {
+                    windows::Win32::System::Com::CoTaskMemAlloc(bytes.len()) as *mut u8
+                };
+                assert!(bytes.is_empty() || !buffer_ptr.is_null(), "CoTaskMemAlloc failed for in-out array");
+                unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer_ptr, bytes.len()) };
+                in_out_arrays.push(Box::new(FillArraySlot {
+                    capacity: array_data.len() as u32,
+                    buffer_ptr,
+                    actual_count: 0,
+                    element_type: p.typ.array_element_type(),
+                }));
+            } else {
+                // Bool travels as its ABI byte, so any byte the callee writes is valid
+                let mut buffer = p.typ.out_buffer();
+                match (&mut buffer, arg) {
+                    (WinRTValue::U8(byte), WinRTValue::Bool(b)) => *byte = u8::from(*b),
+                    (buffer, arg) if std::mem::discriminant(&*buffer) == std::mem::discriminant(arg) => {
+                        *buffer = arg.clone();
+                    }
+                    _ => {
+                        return Err(invalid_arg(format!(
+                            "in-out argument {:?} does not match parameter type {:?}",
+                            arg.get_type_kind(), p.typ.kind()
+                        )));
+                    }
+                }
+                in_out_buffers.push(buffer);
+            }
+        } else if !p.is_out() && p.typ.is_array() {
             let array_data = args[p.value_index]
                 .as_array()
                 .expect("Expected WinRTValue::Array for array in-parameter");
@@ -247,14 +402,36 @@ pub fn call_winrt_method_dynamic(
                 data_ptr,
                 _buffer: buffer,
             }));
+        } else if p.by_pointer {
+            let WinRTValue::Struct(data) = &args[p.value_index] else {
+                panic!("Expected WinRTValue::Struct for struct in-parameter");
+            };
+            struct_in_copies.push(data.clone());
         }
     }
+    // Heap storage, so the pointers stay valid while the Vec is not touched again
+    struct_in_ptrs.extend(struct_in_copies.iter().map(|data| data.as_ptr()));
+    // Scalars live inline in the Vec, which is not resized from here on
+    in_out_ptrs.extend(in_out_buffers.iter_mut().map(|buffer| buffer.out_ptr()));
 
     // Phase 2: Build ffi_args
     let mut array_in_idx = 0usize;
     let mut array_out_idx = 0usize;
+    let mut struct_in_idx = 0usize;
+    let (mut in_out_array_idx, mut in_out_value_idx) = (0usize, 0usize);
     for p in parameters {
-        if p.is_out() {
+        if p.is_in_out() {
+            if p.typ.is_array() {
+                // In-out array: push TWO args (length value, pointer to the copy)
+                let slot = &*in_out_arrays[in_out_array_idx];
+                ffi_args.push(arg(&slot.capacity));
+                ffi_args.push(arg(&slot.buffer_ptr));
+                in_out_array_idx += 1;
+            } else {
+                ffi_args.push(arg(&in_out_ptrs[in_out_value_idx]));
+                in_out_value_idx += 1;
+            }
+        } else if p.is_out() {
             if let Some(slot_idx) = fill_array_map[p.value_index] {
                 // FillArray: push THREE args (capacity, buffer pointer, actual count pointer)
                 let slot = &*fill_array_slots[slot_idx];
@@ -275,7 +452,11 @@ pub fn call_winrt_method_dynamic(
             ffi_args.push(arg(&slot.length));
             ffi_args.push(arg(&slot.data_ptr));
             array_in_idx += 1;
+        } else if p.by_pointer {
+            ffi_args.push(arg(&struct_in_ptrs[struct_in_idx]));
+            struct_in_idx += 1;
         } else {
+            // Borrows `args`, which the caller keeps alive until after `cif.call`
             ffi_args.push(args[p.value_index].libffi_arg());
         }
     }
@@ -304,15 +485,9 @@ pub fn call_winrt_method_dynamic(
                 // ReceiveArray: wrap callee-allocated CoTaskMem buffer directly.
                 // ArrayData takes ownership and will CoTaskMemFree + release elements on drop.
                 let slot = &array_out_slots[slot_idx];
-                let length = slot.length as usize;
-                let data_ptr = slot.data_ptr;
-                let array_value = if data_ptr.is_null() || length == 0 {
-                    crate::array::ArrayData::empty(slot.element_type.clone())
-                } else {
-                    crate::array::ArrayData::from_cotaskmem(
-                        slot.element_type.clone(), data_ptr, length,
-                    )
-                };
+                let array_value = crate::array::ArrayData::from_receive_array(
+                    slot.element_type.clone(), slot.data_ptr, slot.length,
+                );
                 result_values.push(WinRTValue::Array(array_value));
             } else if let Some(struct_val) = struct_out_values[p.value_index].take() {
                 result_values.push(WinRTValue::Struct(struct_val));
@@ -324,5 +499,23 @@ pub fn call_winrt_method_dynamic(
             }
         }
     }
+
+    // In-out params follow the regular outs, in declaration order
+    let mut in_out_arrays = in_out_arrays.into_iter();
+    let mut in_out_buffers = in_out_buffers.into_iter();
+    for p in parameters.iter().filter(|p| p.is_in_out()) {
+        if p.typ.is_array() {
+            let mut slot = in_out_arrays.next().unwrap();
+            // ArrayData takes over the buffer; FillArraySlot::drop must not free it
+            let ptr = std::mem::replace(&mut slot.buffer_ptr, std::ptr::null_mut());
+            result_values.push(WinRTValue::Array(crate::array::ArrayData::from_cotaskmem(
+                slot.element_type.clone(), ptr as *mut c_void, slot.capacity as usize,
+            )));
+        } else {
+            let buffer = in_out_buffers.next().unwrap();
+            let value = p.typ.take_out(buffer).map_err(|e| invalid_arg(e.message()))?;
+            result_values.push(value);
+        }
+    }
     Ok(result_values)
 }
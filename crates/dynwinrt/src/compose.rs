@@ -0,0 +1,99 @@
+use windows_core::{GUID, HSTRING};
+
+use crate::metadata_table::MetadataTable;
+use crate::result::Result;
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
+
+/// A composable object from `CreateInstance(outer, out inner, out instance)`.
+pub struct Composed {
+    /// The requested interface on the new object.
+    pub instance: WinRTValue,
+    /// The non-delegating inner `IInspectable`, or `Null` if the factory did not
+    /// return one. Only meaningful when an outer object was supplied.
+    pub inner: WinRTValue,
+}
+
+impl WinRTValue {
+    /// Activate a composable class without aggregation, via `CreateInstance`
+    /// at vtable index 6 of its composable factory interface `factory_iid`.
+    ///
+    /// Aggregation rules: with a null outer the object is standalone and the
+    /// inner reference (if any) is just another reference to it, so it is
+    /// released here. To derive from a class, use `create_composed` with an
+    /// outer object instead: the outer must keep `inner` alive for its own
+    /// lifetime and forward `QueryInterface` for interfaces it does not
+    /// implement to `inner`, while `instance` shares the outer's refcount.
+    pub fn compose(class_name: &HSTRING, factory_iid: &GUID) -> Result<WinRTValue> {
+        let factory = crate::roapi::ro_get_activation_factory_for(class_name, factory_iid)?;
+        Ok(factory.create_composed(6, &WinRTValue::Null)?.instance)
+    }
+
+    /// Call a composable factory method shaped
+    /// `CreateInstance(IInspectable* outer, IInspectable** inner, T** instance)`,
+    /// the trailing parameters of every composable factory method. `self` must
+    /// be the factory, already cast to the interface that owns `method_index`.
+    pub fn create_composed(&self, method_index: usize, outer: &WinRTValue) -> Result<Composed> {
+        let table = MetadataTable::new();
+        let mut outs = self.call(
+            method_index,
+            &[
+                ParamSpec::In(table.object()),
+                ParamSpec::Out(table.object()),
+                ParamSpec::Out(table.object()),
+            ],
+            std::slice::from_ref(outer),
+        )?;
+        let instance = outs.remove(1);
+        let inner = outs.remove(0);
+        Ok(Composed { instance, inner })
+    }
+}
+
+#[cfg(all(test, feature = "dynamic-call"))]
+mod tests {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+
+    use windows_core::{HRESULT, IUnknown, Interface};
+
+    use super::*;
+    use crate::mock::MockObject;
+
+    thread_local! {
+        static SAW_NULL_OUTER: Cell<bool> = const { Cell::new(false) };
+    }
+
+    // Slot 6 = CreateInstance: the mock acts as both the composable factory and
+    // the product it hands out, so a single refcount covers every reference
+    unsafe extern "system" fn create_instance(
+        this: *mut c_void,
+        outer: *mut c_void,
+        inner: *mut *mut c_void,
+        instance: *mut *mut c_void,
+    ) -> HRESULT {
+        SAW_NULL_OUTER.with(|s| s.set(outer.is_null()));
+        unsafe {
+            let this = IUnknown::from_raw_borrowed(&this).unwrap();
+            *inner = this.clone().into_raw();
+            *instance = this.clone().into_raw();
+        }
+        HRESULT(0)
+    }
+
+    #[test]
+    fn create_composed_returns_owned_inner_and_instance() {
+        let mock = MockObject::new(&[create_instance as *const c_void]);
+        let factory = mock.value();
+
+        let composed = factory.create_composed(6, &WinRTValue::Null).unwrap();
+        assert!(SAW_NULL_OUTER.with(Cell::get));
+        assert_eq!(composed.instance, factory);
+        assert_eq!(composed.inner, factory);
+        assert_eq!(mock.refs(), 3);
+
+        drop(composed);
+        drop(factory);
+        assert_eq!(mock.refs(), 0);
+    }
+}
@@ -1,14 +1,15 @@
 use std::future::{Future, IntoFuture};
 use std::pin::Pin;
+use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll, Waker};
 
 use windows::core::Interface;
 use windows_core::{GUID, HRESULT, IUnknown};
-use windows_future::{AsyncActionCompletedHandler, AsyncStatus};
+use windows_future::{AsyncActionCompletedHandler, AsyncStatus, IAsyncInfo};
 
 use crate::result::{Error, Result};
-use crate::metadata_table::IASYNC_ACTION;
+use crate::metadata_table::{IASYNC_ACTION, TypeHandle};
 use crate::value::WinRTValue;
 
 // ---------------------------------------------------------------------------
@@ -119,9 +120,28 @@ impl DynCompletedHandler {
 
 use crate::value::AsyncInfo;
 
+/// RO_E_CLOSED: the async operation was closed or its results already taken.
+const RO_E_CLOSED: HRESULT = HRESULT(0x80000013u32 as i32);
+
+fn async_error(e: windows_core::Error) -> Error {
+    if e.code() == RO_E_CLOSED {
+        Error::AsyncResultConsumed
+    } else {
+        Error::WindowsError(e)
+    }
+}
+
 pub struct WinRTAsyncFuture {
     async_info: AsyncInfo,
     waker: Option<Arc<Mutex<Waker>>>,
+    /// Set once `poll` has returned `Ready`; later polls must not call GetResults again.
+    completed: bool,
+    /// Set once this future won the shared `consumed` flag. A future dropped
+    /// while pending keeps the claim: its completion handler is registered,
+    /// and an operation accepts only one.
+    claimed: bool,
+    /// Call `IAsyncInfo::Close` once results have been read.
+    close_on_complete: bool,
 }
 
 // WinRT async operations are agile objects and safe to send across threads.
@@ -130,13 +150,22 @@ unsafe impl Send for WinRTAsyncFuture {}
 impl WinRTAsyncFuture {
     fn from_value(value: WinRTValue) -> Self {
         match value {
-            WinRTValue::Async(a) => Self { async_info: a, waker: None },
+            WinRTValue::Async(a) => Self::from_async_info(a),
             _ => panic!("WinRTAsyncFuture::from_value called with non-async WinRTValue"),
         }
     }
 
     fn from_async_info(info: AsyncInfo) -> Self {
-        Self { async_info: info, waker: None }
+        Self { async_info: info, waker: None, completed: false, claimed: false, close_on_complete: false }
+    }
+
+    /// Close the operation (`IAsyncInfo::Close`) right after a successful
+    /// await, releasing server resources before the last reference drops.
+    /// Closing happens strictly after GetResults: a closed operation has no
+    /// results left to read. Failed and canceled operations are not closed.
+    pub fn close_on_complete(mut self, on: bool) -> Self {
+        self.close_on_complete = on;
+        self
     }
 
     /// QI from IAsyncInfo to the concrete async interface.
@@ -160,24 +189,31 @@ impl WinRTAsyncFuture {
     }
 
     /// Call GetResults on the concrete async interface and return the WinRTValue.
+    /// Failed and canceled operations are reported from `Status`/`ErrorCode`
+    /// rather than from whatever GetResults returns for them.
     fn get_results(&self) -> Result<WinRTValue> {
+        match self.async_info.info.Status().map_err(async_error)? {
+            AsyncStatus::Error => {
+                let code = self.async_info.info.ErrorCode().map_err(async_error)?;
+                return Err(Error::AsyncFailed(code));
+            }
+            AsyncStatus::Canceled => return Err(Error::AsyncCanceled),
+            _ => {}
+        }
         let concrete = self.query_concrete()?;
         let (_, get_results_index) = self.vtable_indices();
 
         if let Some(rt) = self.async_info.result_type() {
-            let mut out = rt.default_winrt_value();
+            let mut out = rt.out_buffer();
             let hr = crate::call::call_winrt_method_1(
                 get_results_index,
                 concrete.as_raw(),
                 out.out_ptr(),
             );
-            hr.ok().map_err(Error::WindowsError)?;
-            // Pointer types use RawPtr(null) as buffer; convert via from_out.
-            if let WinRTValue::RawPtr(raw_ptr) = out {
-                out = rt.from_out(raw_ptr)?;
-            }
-            out.sanitize_null_object();
-            Ok(out)
+            hr.ok().map_err(async_error)?;
+            // GetResults hands us an AddRef'd pointer, so take_out takes ownership
+            // and the resulting Object outlives the async operation.
+            rt.take_out(out)
         } else {
             let mut dummy: *mut std::ffi::c_void = std::ptr::null_mut();
             let hr = crate::call::call_winrt_method_1(
@@ -185,8 +221,8 @@ impl WinRTAsyncFuture {
                 concrete.as_raw(),
                 &mut dummy,
             );
-            hr.ok().map_err(Error::WindowsError)?;
-            Ok(WinRTValue::HResult(HRESULT(0)))
+            hr.ok().map_err(async_error)?;
+            Ok(WinRTValue::Unit)
         }
     }
 
@@ -225,12 +261,44 @@ impl Future for WinRTAsyncFuture {
     type Output = Result<WinRTValue>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.completed {
+            return Poll::Ready(Err(Error::AsyncResultConsumed));
+        }
+        // Claim the results before registering SetCompleted or calling
+        // GetResults, so a clone awaited concurrently gets AsyncResultConsumed
+        // instead of racing this future for them
+        if !self.claimed {
+            if self.async_info.consumed
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err()
+            {
+                return Poll::Ready(Err(Error::AsyncResultConsumed));
+            }
+            self.claimed = true;
+        }
+        let poll = self.poll_results(cx);
+        if let Poll::Ready(result) = &poll {
+            self.completed = true;
+            if result.is_err() {
+                // Nothing was taken; release the claim for another await
+                self.async_info.consumed.store(false, Ordering::SeqCst);
+            } else if self.close_on_complete {
+                // The results are already ours; a failed Close loses nothing
+                let _ = self.async_info.info.Close();
+            }
+        }
+        poll
+    }
+}
+
+impl WinRTAsyncFuture {
+    fn poll_results(&mut self, cx: &mut Context<'_>) -> Poll<Result<WinRTValue>> {
         // Fast path: already completed before first poll
         match self.async_info.info.Status() {
             Ok(status) if status != AsyncStatus::Started => {
                 return Poll::Ready(self.get_results());
             }
-            Err(e) => return Poll::Ready(Err(Error::WindowsError(e))),
+            Err(e) => return Poll::Ready(Err(async_error(e))),
             _ => {}
         }
 
@@ -244,7 +312,7 @@ impl Future for WinRTAsyncFuture {
                 Ok(status) if status != AsyncStatus::Started => {
                     return Poll::Ready(self.get_results());
                 }
-                Err(e) => return Poll::Ready(Err(Error::WindowsError(e))),
+                Err(e) => return Poll::Ready(Err(async_error(e))),
                 _ => {}
             }
         } else {
@@ -286,6 +354,67 @@ impl IntoFuture for &WinRTValue {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Call chains — sync calls interleaved with awaits, short-circuit on failure
+// ---------------------------------------------------------------------------
+
+/// One step of a call chain: receives the previous step's (awaited) result.
+pub type ChainStep = Box<dyn Fn(WinRTValue) -> Result<WinRTValue> + Send + Sync>;
+
+/// Await `value` if it is an async operation, otherwise return it unchanged.
+async fn await_if_async(value: WinRTValue) -> Result<WinRTValue> {
+    match value {
+        WinRTValue::Async(_) => value.await,
+        other => Ok(other),
+    }
+}
+
+impl WinRTValue {
+    /// Wrap `info` as an `Async` value of type `ty`, checking that `ty` is an
+    /// async type and that `info` QIs to its interface. Pairing an operation
+    /// with the wrong type would otherwise only fail when awaited, with the
+    /// wrong vtable slots.
+    pub fn from_async(info: IAsyncInfo, ty: &TypeHandle) -> Result<WinRTValue> {
+        let iid = ty.iid().filter(|_| ty.is_async()).ok_or(Error::AsyncTypeMismatch(ty.kind()))?;
+        let mut ptr = std::ptr::null_mut();
+        unsafe { info.query(&iid, &mut ptr) }
+            .ok()
+            .map_err(|_| Error::AsyncTypeMismatch(ty.kind()))?;
+        // Only checking: the concrete interface is QI'd again when awaited
+        drop(unsafe { IUnknown::from_raw(ptr) });
+        Ok(WinRTValue::Async(AsyncInfo::new(info, ty.clone())))
+    }
+
+    /// Run `steps` in order, feeding each the awaited result of the previous one.
+    ///
+    /// The first failing step (sync call or awaited result) stops the chain and is
+    /// reported as `Error::CallFailed(step_index, inner)`.
+    pub async fn pipeline(self, steps: Vec<ChainStep>) -> Result<WinRTValue> {
+        let mut value = self;
+        for (index, step) in steps.iter().enumerate() {
+            let fail = |e: Error| Error::CallFailed(index, Box::new(e));
+            let next = step(value).map_err(fail)?;
+            value = await_if_async(next).await.map_err(fail)?;
+        }
+        Ok(value)
+    }
+}
+
+impl crate::metadata_table::MethodHandle {
+    /// Invoke and, if the first out value is an async operation, await it.
+    pub async fn call_and_await(
+        &self,
+        obj: *mut std::ffi::c_void,
+        args: &[WinRTValue],
+    ) -> Result<WinRTValue> {
+        let mut results = self.invoke(obj, args)?;
+        if results.is_empty() {
+            return Ok(WinRTValue::Unit);
+        }
+        await_if_async(results.swap_remove(0)).await
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Progress handler — reuses delegate infrastructure
 // ---------------------------------------------------------------------------
@@ -371,15 +500,221 @@ mod tests {
             .map_err(Error::WindowsError)?;
 
         let reg = MetadataTable::new();
-        let value = WinRTValue::Async(AsyncInfo {
-            info: async_info,
-            async_type: reg.async_action(),
+        let value = WinRTValue::Async(AsyncInfo::new(async_info, reg.async_action()));
+        assert_eq!(value.await?, WinRTValue::Unit);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_action_reports_error_code() -> Result<()> {
+        let e_accessdenied = windows_core::HRESULT(0x80070005u32 as i32);
+        let handler = WorkItemHandler::new(move |_| Err(windows_core::Error::from_hresult(e_accessdenied)));
+        let op = ThreadPool::RunAsync(&handler)
+            .map_err(Error::WindowsError)?;
+
+        let reg = MetadataTable::new();
+        let info = op.cast().map_err(Error::WindowsError)?;
+        let value = WinRTValue::Async(AsyncInfo::new(info, reg.async_action()));
+        match value.await {
+            Err(Error::AsyncFailed(hr)) => assert_eq!(hr, e_accessdenied),
+            other => panic!("expected AsyncFailed, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_canceled_action_reports_canceled() -> Result<()> {
+        let handler = WorkItemHandler::new(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            Ok(())
+        });
+        let op = ThreadPool::RunAsync(&handler)
+            .map_err(Error::WindowsError)?;
+        let async_info: IAsyncInfo = op.cast()
+            .map_err(Error::WindowsError)?;
+        async_info.Cancel().map_err(Error::WindowsError)?;
+
+        let reg = MetadataTable::new();
+        let value = WinRTValue::Async(AsyncInfo::new(async_info, reg.async_action()));
+        assert!(matches!(value.await, Err(Error::AsyncCanceled)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_await_results_twice_reports_consumed() -> Result<()> {
+        let handler = WorkItemHandler::new(|_| Ok(()));
+        let op = ThreadPool::RunAsync(&handler)
+            .map_err(Error::WindowsError)?;
+        let async_info: IAsyncInfo = op.cast()
+            .map_err(Error::WindowsError)?;
+
+        let reg = MetadataTable::new();
+        let value = WinRTValue::Async(AsyncInfo::new(async_info, reg.async_action()));
+        let mut future = (&value).into_future();
+        (&mut future).await?;
+        // Polling the same future again must not re-issue GetResults
+        assert!(matches!((&mut future).await, Err(Error::AsyncResultConsumed)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_await_cloned_async_reports_consumed() -> Result<()> {
+        let handler = WorkItemHandler::new(|_| Ok(()));
+        let op = ThreadPool::RunAsync(&handler)
+            .map_err(Error::WindowsError)?;
+        let async_info: IAsyncInfo = op.cast()
+            .map_err(Error::WindowsError)?;
+
+        let reg = MetadataTable::new();
+        let value = WinRTValue::Async(AsyncInfo::new(async_info, reg.async_action()));
+        let clone = value.clone();
+        assert_eq!(value.await?, WinRTValue::Unit);
+        // The clone shares the operation, whose results are already taken
+        assert!(matches!(clone.await, Err(Error::AsyncResultConsumed)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_awaits_let_one_claim_results() -> Result<()> {
+        let (release, wait) = std::sync::mpsc::channel::<()>();
+        let wait = std::sync::Mutex::new(wait);
+        let handler = WorkItemHandler::new(move |_| {
+            let _ = wait.lock().unwrap().recv();
+            Ok(())
         });
-        let _result = value.await?;
-        println!("IAsyncAction completed successfully");
+        let op = ThreadPool::RunAsync(&handler)
+            .map_err(Error::WindowsError)?;
+        let async_info: IAsyncInfo = op.cast()
+            .map_err(Error::WindowsError)?;
+
+        let reg = MetadataTable::new();
+        let value = WinRTValue::Async(AsyncInfo::new(async_info, reg.async_action()));
+        let clone = value.clone();
+
+        // The first await claims the results while the work item still runs
+        let mut first = (&value).into_future();
+        assert!(futures::poll!(&mut first).is_pending());
+        assert!(matches!(clone.await, Err(Error::AsyncResultConsumed)));
+
+        release.send(()).unwrap();
+        assert_eq!(first.await?, WinRTValue::Unit);
         Ok(())
     }
 
+    #[test]
+    fn test_from_async_checks_type() -> Result<()> {
+        let handler = WorkItemHandler::new(|_| Ok(()));
+        let op = ThreadPool::RunAsync(&handler).map_err(Error::WindowsError)?;
+        let info: IAsyncInfo = op.cast().map_err(Error::WindowsError)?;
+        let reg = MetadataTable::new();
+
+        let value = WinRTValue::from_async(info.clone(), &reg.async_action())?;
+        assert!(matches!(&value, WinRTValue::Async(a) if a.async_type.kind() == reg.async_action().kind()));
+
+        // An action is not an IAsyncOperation<String>
+        let op_type = reg.async_operation(&reg.hstring());
+        assert!(matches!(
+            WinRTValue::from_async(info.clone(), &op_type),
+            Err(Error::AsyncTypeMismatch(kind)) if kind == op_type.kind()
+        ));
+        // Not an async type at all
+        assert!(matches!(
+            WinRTValue::from_async(info, &reg.object()),
+            Err(Error::AsyncTypeMismatch(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_close_on_complete_closes_async_info() -> Result<()> {
+        let reg = MetadataTable::new();
+        let run = |close: bool| -> Result<(IAsyncInfo, super::WinRTAsyncFuture)> {
+            let handler = WorkItemHandler::new(|_| Ok(()));
+            let op = ThreadPool::RunAsync(&handler).map_err(Error::WindowsError)?;
+            let info: IAsyncInfo = op.cast().map_err(Error::WindowsError)?;
+            let value = WinRTValue::Async(AsyncInfo::new(info.clone(), reg.async_action()));
+            Ok((info, value.into_future().close_on_complete(close)))
+        };
+
+        let (info, future) = run(true)?;
+        assert_eq!(future.await?, WinRTValue::Unit);
+        // A closed IAsyncInfo rejects everything but Close
+        assert!(info.Status().is_err());
+
+        let (info, future) = run(false)?;
+        assert_eq!(future.await?, WinRTValue::Unit);
+        assert_eq!(info.Status().map_err(Error::WindowsError)?, windows_future::AsyncStatus::Completed);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "dynamic-call")]
+    async fn test_await_runtime_class_result_is_owned_object() -> Result<()> {
+        use windows::Storage::{IStorageFile, IStorageFileStatics, IStorageItem};
+        use crate::signature::ParamSpec;
+        let _ = unsafe {
+            windows::Win32::System::WinRT::RoInitialize(windows::Win32::System::WinRT::RO_INIT_MULTITHREADED)
+        };
+
+        let path = std::env::temp_dir().join("dynwinrt_await_storage_file.txt");
+        std::fs::write(&path, b"dynwinrt").unwrap();
+
+        // IStorageFileStatics::GetFileFromPathAsync at vtable index 6, fully dynamic
+        let reg = MetadataTable::new();
+        let file_type = reg.runtime_class("Windows.Storage.StorageFile".into(), IStorageFile::IID);
+        let op_type = reg.async_operation(&file_type);
+        let statics = crate::roapi::ro_get_activation_factory_for(
+            &windows_core::HSTRING::from("Windows.Storage.StorageFile"),
+            &IStorageFileStatics::IID,
+        )?;
+        let op = statics.call(
+            6,
+            &[ParamSpec::In(reg.hstring()), ParamSpec::Out(op_type)],
+            &[WinRTValue::HString(path.to_string_lossy().as_ref().into())],
+        )?.remove(0);
+        assert!(matches!(op, WinRTValue::Async(_)));
+
+        let file = op.await?;
+        let WinRTValue::Object(_) = &file else {
+            panic!("Expected Object result, got {:?}", file.get_type_kind());
+        };
+
+        // The awaited object is owned: QI and a dynamic getter work after the op is gone.
+        // IStorageItem::get_Name at vtable index 11
+        let item = file.cast(&IStorageItem::IID)?;
+        let name = item.call(11, &[ParamSpec::Out(reg.hstring())], &[])?.remove(0);
+        assert_eq!(
+            name.as_hstring().unwrap().to_string(),
+            "dynwinrt_await_storage_file.txt",
+        );
+
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_reports_failing_step() {
+        use windows::Foundation::{IStringable, Uri};
+        use windows_core::h;
+
+        let uri = Uri::CreateUri(h!("https://www.example.com")).unwrap();
+        let start = WinRTValue::Object(uri.cast().unwrap());
+
+        let bogus_iid = windows_core::GUID::from_u128(0x00000000_dead_beef_0000_000000000000);
+        let steps: Vec<super::ChainStep> = vec![
+            Box::new(|v| v.cast(&IStringable::IID)),
+            Box::new(move |v| v.cast(&bogus_iid)),
+            Box::new(|_| panic!("chain must stop at the failing step")),
+        ];
+
+        let err = start.pipeline(steps).await.unwrap_err();
+        assert_eq!(err.failed_call_index(), Some(1));
+        match err {
+            Error::CallFailed(_, inner) => assert!(matches!(*inner, Error::WindowsError(_))),
+            other => panic!("Expected CallFailed, got {:?}", other),
+        }
+    }
+
     /// Verify progress handler IID computation matches windows-rs for known types.
     #[test]
     fn test_progress_handler_iid_u64_u64() {
@@ -438,10 +773,7 @@ mod tests {
         let p_u64 = reg.make(TypeKind::U64);
         let async_type = reg.async_operation_with_progress(&t_u64, &p_u64);
 
-        let async_info = AsyncInfo {
-            info,
-            async_type: async_type.clone(),
-        };
+        let async_info = AsyncInfo::new(info, async_type.clone());
 
         // Set up progress handler
         let progress_count = Arc::new(AtomicU32::new(0));
@@ -547,10 +879,7 @@ mod tests {
         let p_u64 = reg.make(TypeKind::U64);
         let async_type = reg.async_operation_with_progress(&t_u64, &p_u64);
 
-        let value = WinRTValue::Async(AsyncInfo {
-            info,
-            async_type,
-        });
+        let value = WinRTValue::Async(AsyncInfo::new(info, async_type));
 
         let result = value.await?;
         println!("WithProgress (no handler) completed: {:?}", result);
@@ -589,7 +918,7 @@ mod tests {
         let p_u32 = reg.make(TypeKind::U32);
         let async_type = reg.async_operation_with_progress(&t_u32, &p_u32);
 
-        let value = WinRTValue::Async(AsyncInfo { info, async_type });
+        let value = WinRTValue::Async(AsyncInfo::new(info, async_type));
         let result = value.await?;
         println!("dynwinrt WriteAsync result: {:?}", result);
 
@@ -659,7 +988,7 @@ mod tests {
         let p_u64 = reg.make(TypeKind::U64);
         let async_type = reg.async_operation_with_progress(&t_u64, &p_u64);
 
-        let value = WinRTValue::Async(AsyncInfo { info, async_type });
+        let value = WinRTValue::Async(AsyncInfo::new(info, async_type));
         let result = value.await?;
         println!("dynwinrt BufferAllAsync: {:?}", result);
 
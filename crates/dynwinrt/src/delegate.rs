@@ -174,7 +174,10 @@ impl DynamicDelegate {
 
         for (i, pt) in delegate.param_types.iter().enumerate() {
             if i < raw_args.len() {
-                values.push(marshal_abi_ptr(raw_args[i], pt));
+                match marshal_abi_ptr(raw_args[i], pt) {
+                    Ok(value) => values.push(value),
+                    Err(e) => return error_hresult(&e),
+                }
             }
         }
 
@@ -193,7 +196,10 @@ impl DynamicDelegate {
         let mut values = Vec::with_capacity(delegate.param_types.len());
 
         if delegate.param_types.len() >= 1 {
-            values.push(marshal_abi_ptr(arg0, &delegate.param_types[0]));
+            match marshal_abi_ptr(arg0, &delegate.param_types[0]) {
+                Ok(value) => values.push(value),
+                Err(e) => return error_hresult(&e),
+            }
         }
         if delegate.param_types.len() >= 2 {
             values.push(WinRTValue::F64(arg1));
@@ -203,35 +209,31 @@ impl DynamicDelegate {
     }
 }
 
+/// HRESULT `Invoke` fails with when an argument cannot be decoded; the
+/// callback is not run.
+fn error_hresult(e: &crate::result::Error) -> HRESULT {
+    match e {
+        crate::result::Error::WindowsError(e) => e.code(),
+        _ => HRESULT(0x80070057u32 as i32), // E_INVALIDARG
+    }
+}
+
 /// Convert a raw ABI pointer-sized argument to WinRTValue, based on type.
-fn marshal_abi_ptr(raw: *mut c_void, typ: &TypeHandle) -> WinRTValue {
+fn marshal_abi_ptr(raw: *mut c_void, typ: &TypeHandle) -> crate::result::Result<WinRTValue> {
     use crate::metadata_table::TypeKind;
-    match typ.kind() {
-        // Pointer-sized types: wrap as Object (AddRef via from_raw_borrowed + clone)
+    Ok(match typ.kind() {
+        // Reference types are borrowed from the caller for the duration of the
+        // call: AddRef objects and duplicate HSTRINGs instead of taking them over
         TypeKind::Object | TypeKind::Interface(_) | TypeKind::RuntimeClass(_)
-        | TypeKind::Delegate(_) | TypeKind::Parameterized(_) => {
-            if raw.is_null() {
-                WinRTValue::Null
-            } else {
-                let obj = unsafe { IUnknown::from_raw_borrowed(&raw) }.unwrap();
-                WinRTValue::Object(obj.clone())
-            }
-        }
-        // HString: transmute the raw HSTRING handle
-        TypeKind::HString => {
-            if raw.is_null() {
-                WinRTValue::HString(windows_core::HSTRING::new())
-            } else {
-                let hstr: &windows_core::HSTRING = unsafe {
-                    &*(&raw as *const *mut c_void as *const windows_core::HSTRING)
-                };
-                WinRTValue::HString(hstr.clone())
-            }
+        | TypeKind::Delegate(_) | TypeKind::Parameterized(_) | TypeKind::HString
+        | TypeKind::IAsyncAction | TypeKind::IAsyncActionWithProgress(_)
+        | TypeKind::IAsyncOperation(_) | TypeKind::IAsyncOperationWithProgress(_) => {
+            typ.from_out_borrowed(raw)?
         }
         // Small integer types packed into pointer-sized arg
         TypeKind::Bool => WinRTValue::Bool((raw as usize) != 0),
         TypeKind::I32 => WinRTValue::I32(raw as i32),
-        TypeKind::Enum(_) => WinRTValue::Enum { value: raw as i32, type_handle: typ.clone() },
+        TypeKind::Enum(_) => typ.enum_from_bits(raw as isize as i64),
         TypeKind::U32 => WinRTValue::U32(raw as u32),
         TypeKind::I64 => WinRTValue::I64(raw as i64),
         TypeKind::U64 => WinRTValue::U64(raw as u64),
@@ -247,7 +249,7 @@ fn marshal_abi_ptr(raw: *mut c_void, typ: &TypeHandle) -> WinRTValue {
             // Fallback: treat as raw i64 (covers most ABI-compatible cases)
             WinRTValue::I64(raw as i64)
         }
-    }
+    })
 }
 
 // ======================================================================
@@ -0,0 +1,78 @@
+use std::ffi::c_void;
+
+use windows_core::{HRESULT, HSTRING};
+
+use crate::result::{Error, Result};
+use crate::value::WinRTValue;
+
+/// Outcome of `probe_method`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeResult {
+    /// The slot succeeded and wrote this string.
+    HString(String),
+    /// The slot returned a failure HRESULT.
+    Failed(HRESULT),
+}
+
+/// Call vtable slot `index` on `obj` as if it were `HRESULT get_X(HSTRING* out)`
+/// and report what came back. Meant for mapping unknown interfaces by hand.
+///
+/// # Safety
+/// This is a guess about the slot's signature. If the method takes in
+/// parameters, writes something other than an HSTRING (e.g. an object, which
+/// is then freed as a string), or `index` is past the end of the vtable, the
+/// process can crash or corrupt memory. Only probe objects you can afford to
+/// lose, in a throwaway process.
+pub unsafe fn probe_method(obj: &WinRTValue, index: usize) -> Result<ProbeResult> {
+    let obj = obj.as_object().ok_or_else(|| Error::ExpectObjectTypeError(obj.get_type_kind()))?;
+    let mut out: *mut std::ffi::c_void = std::ptr::null_mut();
+    let hr = crate::call::call_winrt_method_1(index, obj.as_raw(), &mut out);
+    if hr.is_err() {
+        return Ok(ProbeResult::Failed(hr));
+    }
+    // A null HSTRING is the empty string; anything else we now own.
+    let s = unsafe { std::mem::transmute::<*mut std::ffi::c_void, HSTRING>(out) };
+    Ok(ProbeResult::HString(String::from_utf16_lossy(s.as_wide())))
+}
+
+/// Read the first `count` function pointers of `obj`'s vtable, from slot 0
+/// (QueryInterface). Subtracting the owning module's base address from each
+/// entry gives RVAs to look up in its symbols when mapping unknown interfaces.
+///
+/// # Safety
+/// A vtable carries no length. `count` must not exceed the number of slots
+/// the interface is known to have (6 for the IInspectable block plus its
+/// methods); reading past the end is undefined behavior.
+pub unsafe fn dump_vtable(obj: &WinRTValue, count: usize) -> Result<Vec<*const c_void>> {
+    let obj = obj.as_object().ok_or_else(|| Error::ExpectObjectTypeError(obj.get_type_kind()))?;
+    let vtable = unsafe { *(obj.as_raw() as *const *const *const c_void) };
+    Ok((0..count).map(|i| unsafe { *vtable.add(i) }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use windows::Foundation::Uri;
+    use windows_core::{IUnknown, Interface, h};
+
+    use super::*;
+
+    #[test]
+    fn probe_uri_scheme_getter() {
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let value = WinRTValue::Object(uri.cast::<IUnknown>().unwrap()).cast(&Uri::IID).unwrap();
+        // IUriRuntimeClass::get_SchemeName at vtable index 17
+        let result = unsafe { probe_method(&value, 17) }.unwrap();
+        assert_eq!(result, ProbeResult::HString("https".to_string()));
+    }
+
+    #[test]
+    fn dump_uri_inspectable_slots() {
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let value = WinRTValue::Object(uri.cast::<IUnknown>().unwrap()).cast(&Uri::IID).unwrap();
+        // IUnknown + IInspectable: every interface has at least these six
+        let slots = unsafe { dump_vtable(&value, 6) }.unwrap();
+        assert_eq!(slots.len(), 6);
+        assert!(slots.iter().all(|p| !p.is_null()));
+        assert!(unsafe { dump_vtable(&WinRTValue::I32(1), 6) }.is_err());
+    }
+}
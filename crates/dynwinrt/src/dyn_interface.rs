@@ -0,0 +1,91 @@
+//! `dyn_interface!`: declare a wrapper over a known interface's vtable
+//! without hand-writing an `InterfaceSignature`.
+
+/// Generate a struct wrapping one interface of a `WinRTValue`, with one
+/// method per listed vtable slot. Each method takes its in params as
+/// `WinRTValue`s and returns its single out value.
+///
+/// Param and return types name `MetadataTable` constructors (`hstring`,
+/// `i32_type`, `object`, ...).
+///
+/// ```ignore
+/// dyn_interface! {
+///     /// Windows.Foundation.IUriRuntimeClass
+///     pub struct DynUri = IUriRuntimeClass::IID {
+///         fn path = 13() -> hstring;
+///         fn combine_uri = 22(relative: hstring) -> object;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! dyn_interface {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident = $iid:path {
+            $(
+                $(#[$method_attr:meta])*
+                fn $method:ident = $slot:literal ( $($arg:ident : $arg_ty:ident),* $(,)? ) -> $ret_ty:ident;
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            value: $crate::WinRTValue,
+            table: ::std::sync::Arc<$crate::MetadataTable>,
+        }
+
+        impl $name {
+            /// QI `value` for the wrapped interface.
+            pub fn new(value: &$crate::WinRTValue) -> $crate::Result<Self> {
+                Ok($name { value: value.cast(&$iid)?, table: $crate::MetadataTable::new() })
+            }
+
+            /// The underlying interface object, for passing to other APIs.
+            pub fn as_value(&self) -> &$crate::WinRTValue {
+                &self.value
+            }
+
+            $(
+                $(#[$method_attr])*
+                pub fn $method(&self, $($arg: $crate::WinRTValue),*) -> $crate::Result<$crate::WinRTValue> {
+                    let params = [
+                        $($crate::ParamSpec::In(self.table.$arg_ty()),)*
+                        $crate::ParamSpec::Out(self.table.$ret_ty()),
+                    ];
+                    Ok(self.value.call($slot, &params, &[$($arg),*])?.remove(0))
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(all(test, feature = "dynamic-call"))]
+mod tests {
+    use windows::Foundation::{IUriRuntimeClass, Uri};
+    use windows_core::{IUnknown, Interface, h};
+
+    use crate::value::WinRTValue;
+
+    crate::dyn_interface! {
+        /// Windows.Foundation.IUriRuntimeClass
+        struct DynUri = IUriRuntimeClass::IID {
+            fn path = 13() -> hstring;
+            fn scheme_name = 17() -> hstring;
+            fn port = 19() -> i32_type;
+            fn combine_uri = 22(relative: hstring) -> object;
+        }
+    }
+
+    #[test]
+    fn dyn_interface_wraps_uri() {
+        let uri = Uri::CreateUri(h!("https://www.example.com/a/b")).unwrap();
+        let uri = DynUri::new(&WinRTValue::Object(uri.cast::<IUnknown>().unwrap())).unwrap();
+        assert_eq!(uri.scheme_name().unwrap(), WinRTValue::HString(h!("https").clone()));
+        assert_eq!(uri.port().unwrap(), WinRTValue::I32(443));
+
+        let combined = uri.combine_uri(WinRTValue::HString(h!("c/d").clone())).unwrap();
+        let combined = DynUri::new(&combined).unwrap();
+        assert!(combined.as_value().as_object().is_some());
+        assert_eq!(combined.path().unwrap(), WinRTValue::HString(h!("/a/c/d").clone()));
+    }
+}
@@ -1,6 +1,12 @@
 use std::sync::Arc;
+use windows_core::{GUID, HSTRING};
 use crate::signature::{InterfaceSignature, MethodSignature};
 use crate::metadata_table::MetadataTable;
+use crate::result::Result;
+use crate::value::WinRTValue;
+
+/// `Windows.Foundation.IUriRuntimeClassFactory`.
+pub const IURI_RUNTIME_CLASS_FACTORY: GUID = GUID::from_u128(0x44a9796f_723e_4fdf_a218_033e75b0c084);
 
 pub fn uri_vtable(reg: &Arc<MetadataTable>) -> InterfaceSignature {
     let mut vtable = InterfaceSignature::define_from_iinspectable(
@@ -26,3 +32,49 @@ pub fn uri_vtable(reg: &Arc<MetadataTable>) -> InterfaceSignature {
         .add_method(MethodSignature::new(reg)); // 20 get_Suspicious;
     vtable
 }
+
+pub fn uri_factory_vtable(reg: &Arc<MetadataTable>) -> InterfaceSignature {
+    let mut vtable = InterfaceSignature::define_from_iinspectable(
+        "Windows.Foundation.IUriRuntimeClassFactory",
+        IURI_RUNTIME_CLASS_FACTORY,
+        reg,
+    );
+    vtable
+        .add_method(MethodSignature::new(reg).add_in(reg.hstring()).add_out(reg.object())) // 6 CreateUri
+        .add_method(
+            MethodSignature::new(reg)
+                .add_in(reg.hstring())
+                .add_in(reg.hstring())
+                .add_out(reg.object()),
+        ); // 7 CreateWithRelativeUri
+    vtable
+}
+
+/// `new Uri(base, relative)` through `IUriRuntimeClassFactory::CreateWithRelativeUri`,
+/// called entirely via `uri_factory_vtable`. Returns the `Uri` object.
+pub fn create_uri_escaped(base: &str, relative: &str) -> Result<WinRTValue> {
+    let reg = MetadataTable::new();
+    let factory = crate::roapi::ro_get_activation_factory_for(
+        &HSTRING::from("Windows.Foundation.Uri"),
+        &IURI_RUNTIME_CLASS_FACTORY,
+    )?;
+    let obj = factory.as_object().ok_or_else(|| crate::result::Error::expect_object_type(factory.get_type_kind()))?;
+    let vtable = uri_factory_vtable(&reg);
+    let mut outs = vtable.methods[7].call_dynamic(
+        obj.as_raw(),
+        &[WinRTValue::HString(HSTRING::from(base)), WinRTValue::HString(HSTRING::from(relative))],
+    )?;
+    Ok(outs.remove(0))
+}
+
+/// `Microsoft.Windows.Storage.Pickers.IPickFileResult` (WinAppSDK 1.8).
+/// The result only carries the picked path; there is no `get_File` slot.
+pub fn pick_file_result_vtable(reg: &Arc<MetadataTable>) -> InterfaceSignature {
+    let mut vtable = InterfaceSignature::define_from_iinspectable(
+        "Microsoft.Windows.Storage.Pickers.IPickFileResult",
+        crate::winapp::IPICK_FILE_RESULT,
+        reg,
+    );
+    vtable.add_method(MethodSignature::new(reg).add_out(reg.hstring())); // 6 get_Path
+    vtable
+}
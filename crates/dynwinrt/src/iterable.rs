@@ -0,0 +1,106 @@
+use crate::metadata_table::TypeHandle;
+use crate::result::Result;
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
+
+/// A WinRT `IIterable<T>` consumed through dynamic calls.
+///
+/// `iter()` calls `First()` (vtable index 6); the returned `DynIterator`
+/// drives `get_Current` (6), `get_HasCurrent` (7) and `MoveNext` (8) and
+/// decodes each element as `element_type`.
+pub struct DynIterable {
+    iterable: WinRTValue,
+    element_type: TypeHandle,
+}
+
+impl DynIterable {
+    /// QI `value` for `IIterable<element_type>`.
+    pub fn new(value: &WinRTValue, element_type: &TypeHandle) -> Result<Self> {
+        let iids = element_type.table().vector_iids(element_type);
+        Ok(DynIterable { iterable: value.cast(&iids.iterable)?, element_type: element_type.clone() })
+    }
+
+    /// `IIterable::First`: a fresh iterator positioned at the first element.
+    pub fn iter(&self) -> Result<DynIterator> {
+        let table = self.element_type.table();
+        let iterator = self.iterable
+            .call(6, &[ParamSpec::Out(table.object())], &[])?
+            .remove(0)
+            .cast(&table.vector_iids(&self.element_type).iterator)?;
+        Ok(DynIterator { iterator, element_type: self.element_type.clone(), started: false, done: false })
+    }
+
+    /// Collect every element, stopping at the first failed call.
+    pub fn to_vec(&self) -> Result<Vec<WinRTValue>> {
+        self.iter()?.collect()
+    }
+}
+
+/// An `IIterator<T>`; yields `Err` once if a call fails, then ends.
+pub struct DynIterator {
+    iterator: WinRTValue,
+    element_type: TypeHandle,
+    started: bool,
+    done: bool,
+}
+
+impl DynIterator {
+    fn advance(&mut self) -> Result<Option<WinRTValue>> {
+        let table = self.element_type.table();
+        // The first element needs no MoveNext: First() already points at it
+        let index = if self.started { 8 } else { 7 };
+        self.started = true;
+        let has_current = self.iterator.call(index, &[ParamSpec::Out(table.bool_type())], &[])?;
+        if !matches!(has_current.first(), Some(WinRTValue::Bool(true))) {
+            return Ok(None);
+        }
+        let current = self.iterator
+            .call(6, &[ParamSpec::Out(self.element_type.clone())], &[])?
+            .remove(0);
+        Ok(Some(current))
+    }
+}
+
+impl Iterator for DynIterator {
+    type Item = Result<WinRTValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.advance().transpose();
+        if !matches!(item, Some(Ok(_))) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use windows_collections::IIterable;
+    use windows_core::{HSTRING, IUnknown, Interface};
+
+    use super::*;
+    use crate::metadata_table::MetadataTable;
+
+    #[test]
+    fn iterate_hstring_iterable() {
+        let items: IIterable<HSTRING> = vec![HSTRING::from("a"), HSTRING::from("b"), HSTRING::from("c")].into();
+        let table = MetadataTable::new();
+        let iterable = DynIterable::new(&WinRTValue::Object(items.cast::<IUnknown>().unwrap()), &table.hstring()).unwrap();
+
+        let values = iterable.to_vec().unwrap();
+        assert_eq!(values, vec![WinRTValue::string("a"), WinRTValue::string("b"), WinRTValue::string("c")]);
+
+        // Each iter() starts over
+        assert_eq!(iterable.iter().unwrap().count(), 3);
+
+        let empty: IIterable<HSTRING> = Vec::<HSTRING>::new().into();
+        let empty = DynIterable::new(&WinRTValue::Object(empty.cast::<IUnknown>().unwrap()), &table.hstring()).unwrap();
+        assert!(empty.to_vec().unwrap().is_empty());
+
+        // Wrong element type fails the QI
+        assert!(DynIterable::new(&WinRTValue::Object(items.cast::<IUnknown>().unwrap()), &table.i32_type()).is_err());
+    }
+}
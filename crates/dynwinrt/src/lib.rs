@@ -1,7 +1,9 @@
 use windows::core::*;
 
 mod abi;
+mod apartment;
 mod call;
+mod compose;
 mod interfaces;
 mod result;
 mod roapi;
@@ -10,24 +12,54 @@ mod value;
 mod winapp;
 
 mod array;
+mod buffer;
 #[macro_use]
 mod com_helpers;
 mod dasync;
+mod dyn_interface;
+mod iterable;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
 pub mod delegate;
 pub mod map;
 mod meta;
+#[cfg(test)]
+mod mock;
+mod property_set;
+mod recognized_text;
+mod retry;
+mod software_bitmap;
+mod runtime;
 pub mod metadata_table;
+pub mod prelude;
 pub mod vector;
 
+pub use crate::apartment::{
+    ApartmentBound, ForeignRelease, ReleaseObserver, clear_release_observer, off_apartment_releases, set_release_observer,
+};
+pub use crate::call::{CallEvent, CallObserver, clear_call_observer, set_call_observer};
+pub use crate::compose::Composed;
 pub use crate::result::Result;
-pub use crate::roapi::ro_get_activation_factory_2;
-pub use crate::signature::{InterfaceSignature, MethodSignature};
+pub use crate::roapi::{activatable_classes, ensure_mta, list_activatable_classes, ro_get_activation_factory_2, ro_get_activation_factory_for};
+pub use crate::signature::{InterfaceSignature, MethodSignature, ParamSpec, SignatureRegistry};
 pub use crate::metadata_table::{TypeHandle, TypeKind, MetadataTable, MethodHandle, ValueTypeData};
 pub use crate::array::ArrayData;
-pub use crate::value::WinRTValue;
-pub use crate::winapp::{WinAppSdkContext, initialize_winappsdk};
+pub use crate::buffer::DynBuffer;
+pub use crate::value::{BorrowedArgs, CloseOnDrop, OutSlot, TrustLevel, WinRTValue};
+pub use crate::winapp::{DynPickFileResult, IPICK_FILE_RESULT, WinAppSdkContext, initialize_winappsdk};
 pub use crate::dasync::{create_progress_handler, ProgressCallback};
-pub use interfaces::uri_vtable;
+#[cfg(feature = "diagnostics")]
+pub use crate::diagnostics::{ProbeResult, dump_vtable, probe_method};
+pub use crate::iterable::{DynIterable, DynIterator};
+pub use crate::meta::{required_interfaces, vtable_index_for};
+pub use crate::property_set::DynPropertySet;
+pub use crate::recognized_text::{DynRecognizedText, IRECOGNIZED_TEXT};
+pub use crate::software_bitmap::{DynSoftwareBitmap, ISOFTWARE_BITMAP};
+pub use crate::runtime::DynRuntime;
+pub use crate::retry::{RetryPolicy, RPC_E_DISCONNECTED, RPC_E_SERVER_UNAVAILABLE};
+pub use interfaces::{
+    IURI_RUNTIME_CLASS_FACTORY, create_uri_escaped, pick_file_result_vtable, uri_factory_vtable, uri_vtable,
+};
 
 pub async fn get_async_string(op_string: windows_future::IAsyncOperation<HSTRING>) -> windows_core::Result<String> {
     let s = op_string.await?;
@@ -137,6 +169,87 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_call_observer_reports_method_index() -> Result<()> {
+        use std::sync::{Arc, Mutex};
+
+        let events: Arc<Mutex<Vec<CallEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        set_call_observer(Box::new(move |e| sink.lock().unwrap().push(e)));
+
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let reg = metadata_table::MetadataTable::new();
+        let vtable = interfaces::uri_vtable(&reg);
+        vtable.methods[19].call_dynamic(uri.as_raw(), &[])?;
+        // Fast getters and fixed-slot helpers are reported too
+        assert_eq!(vtable.methods[17].call_getter_hstring(uri.as_raw())?, "https");
+        let value = WinRTValue::Object(uri.cast()?);
+        assert_eq!(value.to_winrt_string().unwrap(), "https://www.example.com/path");
+        clear_call_observer();
+
+        let events = events.lock().unwrap();
+        assert!(events.iter().any(|e| e.method_index == 19 && e.arg_count == 0 && e.hresult.is_ok()));
+        assert!(events.iter().any(|e| e.method_index == 17 && e.hresult.is_ok()));
+        assert!(events.iter().any(|e| e.method_index == 6 && e.hresult.is_ok()));
+        Ok(())
+    }
+
+    /// Memory-safety contract for dynamic calls: object in-args are borrowed
+    /// (callee AddRefs what it keeps), object outs are owned (exactly one Release
+    /// on drop). Walks an object through a real PropertySet via dynamic calls and
+    /// checks the refcount at every hand-off.
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn test_object_refcount_contract_end_to_end() -> Result<()> {
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        // A plain IInspectable: the map must not find IPropertyValue on it
+        let tracked = crate::mock::MockObject::with_interfaces(&[], &[]);
+        let refs = || tracked.refs();
+
+        let value = tracked.value();
+        assert_eq!(refs(), 1, "WinRTValue owns one reference");
+
+        let set = DynPropertySet::new()?;
+        set.insert("k", value.clone())?;
+        assert_eq!(refs(), 2, "in-arg is borrowed; the map keeps exactly one reference");
+
+        let got = set.get("k")?;
+        assert_eq!(refs(), 3, "out value owns the reference Lookup returned");
+        assert_eq!(got, value);
+        drop(got);
+        assert_eq!(refs(), 2, "dropping an out value releases once");
+
+        // IMap::Remove at vtable index 11
+        let reg = metadata_table::MetadataTable::new();
+        set.as_value().call(11, &[ParamSpec::In(reg.hstring())], &[WinRTValue::HString("k".into())])?;
+        assert_eq!(refs(), 1, "map released its reference on Remove");
+
+        set.insert("k", value.clone())?;
+        drop(set);
+        assert_eq!(refs(), 1, "map released its reference when destroyed");
+
+        // A double release would underflow the mock's count instead of reaching zero
+        drop(value);
+        assert_eq!(refs(), 0, "every reference was released exactly once");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn test_create_uri_with_relative_uri() -> crate::result::Result<()> {
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let uri = create_uri_escaped("https://www.example.com/base/index.html", "../other/page.html?q=1")?;
+        let uri: Uri = uri.as_object().unwrap().cast()?;
+        assert_eq!(uri.Path()?, "/other/page.html");
+        assert_eq!(uri.Query()?, "?q=1");
+        Ok(())
+    }
+
     #[test]
     fn test_uri_call_dynamic() -> Result<()> {
         let uri = Uri::CreateUri(h!("https://www.example.com/path?query=1#fragment")).unwrap();
@@ -148,6 +261,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "dynamic-call")]
     fn test_struct_in_param_geopoint_create() -> Result<()> {
         use windows::Devices::Geolocation::Geopoint;
         use windows::Win32::System::WinRT::{
@@ -208,6 +322,45 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn test_struct_in_param_by_value_and_by_pointer_agree() -> Result<()> {
+        use windows::Devices::Geolocation::{Geopoint, IGeopointFactory};
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        // BasicGeoposition is 24 bytes: by reference on x64, whichever path builds the call
+        let reg = metadata_table::MetadataTable::new();
+        let f64_h = reg.f64_type();
+        let geo_type = reg.struct_type("Windows.Devices.Geolocation.BasicGeoposition", &[f64_h.clone(), f64_h.clone(), f64_h]);
+        let mut geo_val = geo_type.default_value();
+        geo_val.set_field(0, 47.643f64);
+        geo_val.set_field(1, -122.131f64);
+        geo_val.set_field(2, 100.0f64);
+
+        let factory = ro_get_activation_factory_for(h!("Windows.Devices.Geolocation.Geopoint"), &IGeopointFactory::IID)?;
+        let factory = factory.as_object().unwrap();
+        let create = |indirect: bool| -> Result<[f64; 3]> {
+            // IGeopointFactory::Create(BasicGeoposition, out Geopoint) at vtable index 6
+            let method = MethodSignature::new(&reg)
+                .add_in(geo_type.clone())
+                .add_out(reg.object())
+                .pass_structs_indirectly(indirect)
+                .build(6);
+            let outs = method.call_dynamic(factory.as_raw(), &[WinRTValue::Struct(geo_val.clone())])?;
+            let pos = outs[0].as_object().unwrap().cast::<Geopoint>()?.Position()?;
+            Ok([pos.Latitude, pos.Longitude, pos.Altitude])
+        };
+
+        let by_value = create(false)?;
+        assert_eq!(by_value, create(true)?);
+        assert!((by_value[0] - 47.643).abs() < 1e-6);
+        assert!((by_value[2] - 100.0).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
     fn test_struct_out_param_geopoint_position() -> Result<()> {
         use windows::Devices::Geolocation::{BasicGeoposition, Geopoint, IGeopoint};
         use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
@@ -313,6 +466,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_reference_out_unboxes_or_null() -> Result<()> {
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let statics_iid = windows_core::GUID::from_u128(0x629BDBC8_D932_4FF4_96B9_8D96C5C1E858);
+        let statics = ro_get_activation_factory_for(h!("Windows.Foundation.PropertyValue"), &statics_iid)?;
+        let obj = statics.as_object().unwrap();
+        let reg = metadata_table::MetadataTable::new();
+
+        // CreateInt32(i32) at vtable index 10 returns a boxed IReference<Int32>
+        let create_int32 = MethodSignature::new(&reg)
+            .add_in(reg.i32_type())
+            .add_reference_out(reg.i32_type())
+            .build(10);
+        let out = create_int32.call_dynamic(obj.as_raw(), &[WinRTValue::I32(42)])?;
+        assert!(matches!(out[..], [WinRTValue::I32(42)]));
+
+        // CreateEmpty() at vtable index 6 returns null
+        let create_empty = MethodSignature::new(&reg)
+            .add_reference_out(reg.i32_type())
+            .build(6);
+        let out = create_empty.call_dynamic(obj.as_raw(), &[])?;
+        assert!(matches!(out[..], [WinRTValue::Null]));
+
+        Ok(())
+    }
+
+    /// Getters write the callee's value in place into a buffer of the out
+    /// type's own width, so 8-byte and float outs decode exactly.
+    #[test]
+    fn test_wide_and_float_getter_outs() -> Result<()> {
+        use windows::Foundation::{IPropertyValue, PropertyValue};
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let reg = metadata_table::MetadataTable::new();
+        let get = |boxed: IInspectable, index: usize, typ: TypeHandle| -> Result<WinRTValue> {
+            let value = WinRTValue::Object(boxed.cast()?).cast(&IPropertyValue::IID)?;
+            Ok(value.call(index, &[ParamSpec::Out(typ)], &[])?.remove(0))
+        };
+
+        // IPropertyValue: GetUInt64 = 14, GetSingle = 15, GetDouble = 16
+        let big = u64::MAX - 1;
+        assert_eq!(get(PropertyValue::CreateUInt64(big)?, 14, reg.u64_type())?, WinRTValue::U64(big));
+        assert_eq!(get(PropertyValue::CreateSingle(1.25)?, 15, reg.f32_type())?, WinRTValue::F32(1.25));
+        assert_eq!(get(PropertyValue::CreateDouble(-2.5e300)?, 16, reg.f64_type())?, WinRTValue::F64(-2.5e300));
+        Ok(())
+    }
+
     #[test]
     fn test_receive_array_get_int32() -> Result<()> {
         use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
@@ -364,4 +567,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn test_receive_array_copies_and_frees_bytes() -> Result<()> {
+        use windows::Security::Cryptography::{CryptographicBuffer, ICryptographicBufferStatics};
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        // ICryptographicBufferStatics::CopyToByteArray(IBuffer, UINT32* length, BYTE** value) at index 10
+        let reg = metadata_table::MetadataTable::new();
+        let statics = ro_get_activation_factory_for(
+            h!("Windows.Security.Cryptography.CryptographicBuffer"),
+            &ICryptographicBufferStatics::IID,
+        )?;
+        let copy = |bytes: &[u8]| -> Result<Vec<WinRTValue>> {
+            let buffer = CryptographicBuffer::CreateFromByteArray(bytes)?;
+            let mut outs = statics.call(
+                10,
+                &[ParamSpec::In(reg.object()), ParamSpec::Out(reg.array(&reg.u8_type()))],
+                &[WinRTValue::Object(buffer.cast()?)],
+            )?;
+            let array = outs.remove(0);
+            Ok(array.as_array().expect("Expected WinRTValue::Array").to_values())
+        };
+
+        assert_eq!(copy(&[1, 2, 3])?, vec![WinRTValue::U8(1), WinRTValue::U8(2), WinRTValue::U8(3)]);
+        // Empty buffers may come back as (0, null); nothing to free
+        assert!(copy(&[])?.is_empty());
+        Ok(())
+    }
 }
@@ -1,3 +1,264 @@
+use std::sync::Arc;
+
+use windows_core::GUID;
+use windows_metadata::{HasAttributes, Signature, Type, Value, reader};
+
+use crate::metadata_table::{MetadataTable, TypeHandle};
+use crate::result::{Error, Result};
+use crate::signature::MethodSignature;
+
+impl MetadataTable {
+    /// Register runtime class `namespace.class`, reading its `[default]`
+    /// interface IID from winmd instead of requiring the caller to paste it.
+    pub fn runtime_class_from_winmd(
+        self: &Arc<Self>,
+        index: &reader::Index,
+        namespace: &str,
+        class: &str,
+    ) -> Result<TypeHandle> {
+        let full_name = format!("{}.{}", namespace, class);
+        let def = index.get(namespace, class).next()
+            .ok_or_else(|| Error::TypeNotFound(full_name.clone()))?;
+        let default_iid = def.interface_impls()
+            .filter(|i| i.has_attribute("DefaultAttribute"))
+            .find_map(|i| match i.interface(&[]) {
+                Type::Name(tn) if !tn.name.contains('`') => {
+                    index.get(&tn.namespace, &tn.name).next().and_then(|d| guid_attribute(&d))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| Error::NotAnInterface(format!("{} has no non-generic default interface", full_name)))?;
+        Ok(self.runtime_class(full_name, default_iid))
+    }
+
+    /// Register value type `namespace.name` as a struct whose fields, in
+    /// declaration order, are read from winmd (nested structs and enums included).
+    pub fn struct_from_winmd(
+        self: &Arc<Self>,
+        index: &reader::Index,
+        namespace: &str,
+        name: &str,
+    ) -> Result<TypeHandle> {
+        let full_name = format!("{}.{}", namespace, name);
+        let def = index.get(namespace, name).next()
+            .ok_or_else(|| Error::TypeNotFound(full_name.clone()))?;
+        if !def.extends().is_some_and(|e| e.namespace() == "System" && e.name() == "ValueType") {
+            return Err(Error::TypeNotFound(format!("{} is not a value type", full_name)));
+        }
+        let fields = def.fields()
+            .map(|f| self.type_from_metadata(index, &f.ty(), &[]))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.struct_type(&full_name, &fields))
+    }
+
+    /// Map a winmd signature type to a `TypeHandle`, registering any named
+    /// structs, enums and runtime classes it references. `generics` supplies
+    /// the arguments for `Type::Generic(n)` placeholders inside generic
+    /// interface or delegate definitions.
+    pub fn type_from_metadata(
+        self: &Arc<Self>,
+        index: &reader::Index,
+        ty: &Type,
+        generics: &[TypeHandle],
+    ) -> Result<TypeHandle> {
+        Ok(match ty {
+            Type::Bool => self.bool_type(),
+            Type::Char => self.char16_type(),
+            Type::I8 => self.i8_type(),
+            Type::U8 => self.u8_type(),
+            Type::I16 => self.i16_type(),
+            Type::U16 => self.u16_type(),
+            Type::I32 => self.i32_type(),
+            Type::U32 => self.u32_type(),
+            Type::I64 => self.i64_type(),
+            Type::U64 => self.u64_type(),
+            Type::F32 => self.f32_type(),
+            Type::F64 => self.f64_type(),
+            Type::String => self.hstring(),
+            Type::Object => self.object(),
+            Type::Generic(n) => generics.get(*n as usize).cloned().ok_or_else(|| {
+                Error::TypeNotFound(format!("generic parameter {} (have {})", n, generics.len()))
+            })?,
+            Type::Array(inner) | Type::ArrayRef(inner) => {
+                self.array(&self.type_from_metadata(index, inner, generics)?)
+            }
+            Type::Name(tn) => {
+                let args = tn.generics.iter()
+                    .map(|g| self.type_from_metadata(index, g, generics))
+                    .collect::<Result<Vec<_>>>()?;
+                self.named_type_from_metadata(index, &tn.namespace, &tn.name, &args)?
+            }
+            other => return Err(Error::TypeNotFound(format!("{:?}", other))),
+        })
+    }
+
+    fn named_type_from_metadata(
+        self: &Arc<Self>,
+        index: &reader::Index,
+        namespace: &str,
+        name: &str,
+        args: &[TypeHandle],
+    ) -> Result<TypeHandle> {
+        let full_name = format!("{}.{}", namespace, name);
+        match (full_name.as_str(), args) {
+            // Guid lives outside Windows.winmd; HResult is a struct there but a primitive here
+            ("System.Guid", []) => return Ok(self.guid_type()),
+            ("Windows.Foundation.HResult", []) => return Ok(self.hresult()),
+            ("Windows.Foundation.IAsyncAction", []) => return Ok(self.async_action()),
+            ("Windows.Foundation.IAsyncOperation`1", [result]) => return Ok(self.async_operation(result)),
+            ("Windows.Foundation.IAsyncActionWithProgress`1", [progress]) => {
+                return Ok(self.async_action_with_progress(progress));
+            }
+            ("Windows.Foundation.IAsyncOperationWithProgress`2", [result, progress]) => {
+                return Ok(self.async_operation_with_progress(result, progress));
+            }
+            _ => {}
+        }
+
+        let def = index.get(namespace, name).next()
+            .ok_or_else(|| Error::TypeNotFound(full_name.clone()))?;
+
+        if !args.is_empty() {
+            let piid = guid_attribute(&def)
+                .ok_or_else(|| Error::NotAnInterface(full_name.clone()))?;
+            let generic = self.generic(piid, args.len() as u32);
+            return Ok(self.parameterized(&generic, args));
+        }
+
+        let extends = def.extends().map(|e| format!("{}.{}", e.namespace(), e.name()));
+        match extends.as_deref() {
+            Some("System.Enum") => {
+                let mut underlying = self.i32_type();
+                let mut members = Vec::new();
+                for field in def.fields() {
+                    if field.name() == "value__" {
+                        underlying = self.type_from_metadata(index, &field.ty(), &[])?;
+                    } else if let Some(value) = field.constant().and_then(|c| integer_value(&c.value())) {
+                        members.push((field.name().to_string(), value));
+                    }
+                }
+                Ok(self.enum_type_with_underlying(&full_name, &underlying, members))
+            }
+            Some("System.ValueType") => self.struct_from_winmd(index, namespace, name),
+            Some("System.Object") => self.runtime_class_from_winmd(index, namespace, name),
+            Some("System.MulticastDelegate") => {
+                let iid = guid_attribute(&def).ok_or_else(|| Error::NotAnInterface(full_name))?;
+                Ok(self.delegate(iid))
+            }
+            _ => {
+                let iid = guid_attribute(&def).ok_or_else(|| Error::NotAnInterface(full_name.clone()))?;
+                Ok(self.named_interface(&full_name, iid))
+            }
+        }
+    }
+}
+
+impl MethodSignature {
+    /// Translate a winmd method signature: `ByRef` params become outs, `ref T[]`
+    /// a ReceiveArray out, and a non-void return type the trailing retval out.
+    ///
+    /// FillArray params (`T[]` marked `[out]`) differ from PassArray only in
+    /// the param flags, which a `Signature` does not carry, so they come back
+    /// as ins; resolve those from the `MethodDef` params as `DynRuntime` does.
+    pub fn from_winmd_signature(
+        table: &Arc<MetadataTable>,
+        index: &reader::Index,
+        sig: &Signature,
+        generics: &[TypeHandle],
+    ) -> Result<Self> {
+        let mut method = MethodSignature::new(table);
+        for ty in &sig.types {
+            method = match ty {
+                Type::RefMut(inner) => method.add_out(table.type_from_metadata(index, inner, generics)?),
+                Type::ArrayRef(_) => method.add_out(table.type_from_metadata(index, ty, generics)?),
+                _ => method.add_in(table.type_from_metadata(index, ty, generics)?),
+            };
+        }
+        if sig.return_type != Type::Void {
+            method = method.add_retval(table.type_from_metadata(index, &sig.return_type, generics)?);
+        }
+        Ok(method)
+    }
+}
+
+/// Every interface `namespace.interface` requires, directly or through other
+/// required interfaces, in breadth-first order. An interface reached along
+/// several paths (IClosable under IRandomAccessStream) is listed once.
+/// Generic requirements are listed but not expanded.
+pub fn required_interfaces(index: &reader::Index, namespace: &str, interface: &str) -> Result<Vec<(String, String)>> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    let mut pending = std::collections::VecDeque::from([(namespace.to_string(), interface.to_string())]);
+    while let Some((ns, name)) = pending.pop_front() {
+        let def = index.get(&ns, &name).next()
+            .ok_or_else(|| Error::TypeNotFound(format!("{}.{}", ns, name)))?;
+        for required in def.interface_impls() {
+            let Type::Name(tn) = required.interface(&[]) else { continue };
+            let key = (tn.namespace.clone(), tn.name.clone());
+            if found.contains(&key) {
+                continue;
+            }
+            if tn.generics.is_empty() {
+                pending.push_back(key.clone());
+            }
+            found.push(key);
+        }
+    }
+    Ok(found)
+}
+
+/// The interface that declares `method` when called through
+/// `namespace.interface`, and its vtable slot there.
+///
+/// WinRT interfaces do not extend each other's vtables: a required
+/// interface's methods live on its own vtable (reached by QI), and every
+/// vtable is the IInspectable block followed by the interface's own methods.
+/// So the slot is `6 + position` within the declaring interface, never a sum
+/// over the chain.
+pub fn vtable_index_for(
+    index: &reader::Index,
+    namespace: &str,
+    interface: &str,
+    method: &str,
+) -> Result<((String, String), usize)> {
+    let chain = std::iter::once((namespace.to_string(), interface.to_string()))
+        .chain(required_interfaces(index, namespace, interface)?);
+    for (ns, name) in chain {
+        let Some(def) = index.get(&ns, &name).next() else { continue };
+        if let Some(position) = def.methods().position(|m| m.name() == method) {
+            return Ok(((ns, name), 6 + position));
+        }
+    }
+    Err(Error::MethodNotFound(format!("{}.{}", namespace, interface), method.to_string()))
+}
+
+fn integer_value(value: &Value) -> Option<i64> {
+    Some(match value {
+        Value::I8(v) => *v as i64,
+        Value::U8(v) => *v as i64,
+        Value::I16(v) => *v as i64,
+        Value::U16(v) => *v as i64,
+        Value::I32(v) => *v as i64,
+        Value::U32(v) => *v as i64,
+        Value::I64(v) => *v,
+        Value::U64(v) => *v as i64,
+        _ => return None,
+    })
+}
+
+/// Decode a `GuidAttribute(u32, u16, u16, u8 x 8)` into a GUID.
+fn guid_attribute(def: &reader::TypeDef) -> Option<GUID> {
+    let args = def.find_attribute("GuidAttribute")?.value();
+    let values: Vec<u32> = args.iter().map(|(_, v)| match v {
+        Value::U32(x) => Some(*x),
+        Value::U16(x) => Some(*x as u32),
+        Value::U8(x) => Some(*x as u32),
+        _ => None,
+    }).collect::<Option<_>>()?;
+    let [a, b, c, rest @ ..] = values.as_slice() else { return None };
+    let tail: [u8; 8] = rest.iter().map(|x| *x as u8).collect::<Vec<_>>().try_into().ok()?;
+    Some(GUID::from_values(*a, *b as u16, *c as u16, tail))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -195,6 +456,143 @@ mod tests {
         println!("\nTotal distinct array element types: {}", sorted.len());
     }
 
+    #[test]
+    fn runtime_class_default_iid_from_winmd() {
+        use crate::metadata_table::MetadataTable;
+        use windows_core::Interface;
+        use windows_metadata::*;
+
+        let index = reader::Index::read(
+            r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd",
+        )
+        .unwrap();
+        let table = MetadataTable::new();
+        let file = table.runtime_class_from_winmd(&index, "Windows.Storage", "StorageFile").unwrap();
+        assert_eq!(file.iid().unwrap(), windows::Storage::StorageFile::IID);
+        assert_eq!(
+            file.signature_string(),
+            "rc(Windows.Storage.StorageFile;{fa3f6186-4214-428c-a64c-14c9ac7315ea})",
+        );
+
+        assert!(table.runtime_class_from_winmd(&index, "Windows.Storage", "NoSuchClass").is_err());
+    }
+
+    #[test]
+    fn struct_from_winmd_builds_rect_layout() {
+        use crate::metadata_table::{MetadataTable, TypeKind};
+        use windows_metadata::*;
+
+        let index = reader::Index::read(
+            r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd",
+        )
+        .unwrap();
+        let table = MetadataTable::new();
+        let rect = table.struct_from_winmd(&index, "Windows.Foundation", "Rect").unwrap();
+        assert_eq!(rect.size_of(), 16);
+        assert_eq!(rect.field_count(), 4);
+        assert_eq!(rect.signature_string(), "struct(Windows.Foundation.Rect;f4;f4;f4;f4)");
+        assert_eq!(table.type_from_metadata(&index, &Type::named("Windows.Foundation", "Rect"), &[]).unwrap().kind(), rect.kind());
+        assert!(matches!(rect.kind(), TypeKind::Struct(_)));
+
+        assert!(table.struct_from_winmd(&index, "Windows.Foundation", "Uri").is_err());
+    }
+
+    #[test]
+    fn type_from_metadata_maps_primitives_and_named_types() {
+        use crate::metadata_table::{MetadataTable, TypeKind};
+        use windows_core::Interface;
+        use windows_metadata::*;
+
+        let index = reader::Index::read(
+            r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd",
+        )
+        .unwrap();
+        let table = MetadataTable::new();
+
+        assert_eq!(table.type_from_metadata(&index, &Type::I32, &[]).unwrap().kind(), TypeKind::I32);
+        assert_eq!(table.type_from_metadata(&index, &Type::String, &[]).unwrap().kind(), TypeKind::HString);
+        let t = table.type_from_metadata(&index, &Type::Generic(0), &[table.f64_type()]).unwrap();
+        assert_eq!(t.kind(), TypeKind::F64);
+
+        // Enum: members and underlying type come from winmd
+        let e = table
+            .type_from_metadata(&index, &Type::named("Windows.Foundation", "AsyncStatus"), &[])
+            .unwrap();
+        assert!(matches!(e.kind(), TypeKind::Enum(_)));
+        assert_eq!(table.get_enum_value("Windows.Foundation.AsyncStatus", "Completed"), Some(1));
+
+        // Interface and runtime class resolve to their IIDs
+        let uri = table.type_from_metadata(&index, &Type::named("Windows.Foundation", "Uri"), &[]).unwrap();
+        assert_eq!(uri.iid().unwrap(), windows::Foundation::Uri::IID);
+        let iface = table
+            .type_from_metadata(&index, &Type::named("Windows.Foundation", "IStringable"), &[])
+            .unwrap();
+        assert_eq!(iface.iid().unwrap(), windows::Foundation::IStringable::IID);
+
+        // Generic instantiation becomes a parameterized type with the right IID
+        let mut vector = Type::named("Windows.Foundation.Collections", "IVector`1");
+        if let Type::Name(tn) = &mut vector {
+            tn.generics.push(Type::String);
+        }
+        let v = table.type_from_metadata(&index, &vector, &[]).unwrap();
+        assert_eq!(v.iid().unwrap(), windows_collections::IVector::<windows_core::HSTRING>::IID);
+
+        assert!(table.type_from_metadata(&index, &Type::named("Nope", "Missing"), &[]).is_err());
+    }
+
+    #[test]
+    fn method_signature_from_uri_get_path() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::MethodSignature;
+        use crate::value::WinRTValue;
+        use windows::Foundation::{IUriRuntimeClass, Uri};
+        use windows_core::{Interface, h};
+        use windows_metadata::*;
+
+        let index = reader::Index::read(
+            r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd",
+        )
+        .unwrap();
+        let table = MetadataTable::new();
+
+        let def = index.expect("Windows.Foundation", "IUriRuntimeClass");
+        let (slot, get_path) = def.methods().enumerate().find(|(_, m)| m.name() == "get_Path").unwrap();
+        let method = MethodSignature::from_winmd_signature(&table, &index, &get_path.signature(&[]), &[])
+            .unwrap()
+            .build(6 + slot);
+
+        let uri: IUriRuntimeClass = Uri::CreateUri(h!("https://www.example.com/a/b?q=1")).unwrap().cast().unwrap();
+        let outs = method.call_dynamic(uri.as_raw(), &[]).unwrap();
+        assert_eq!(outs, vec![WinRTValue::HString(h!("/a/b").clone())]);
+    }
+
+    #[test]
+    fn required_interfaces_of_random_access_stream() {
+        use windows_metadata::*;
+
+        let index = reader::Index::read(
+            r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd",
+        )
+        .unwrap();
+        let ns = "Windows.Storage.Streams";
+
+        // IInputStream and IOutputStream both require IClosable: listed once
+        let required = super::required_interfaces(&index, ns, "IRandomAccessStream").unwrap();
+        let names: Vec<&str> = required.iter().map(|(_, n)| n.as_str()).collect();
+        for expected in ["IClosable", "IInputStream", "IOutputStream"] {
+            assert_eq!(names.iter().filter(|n| **n == expected).count(), 1, "{expected} in {names:?}");
+        }
+
+        // Own methods start right after IInspectable; inherited ones keep their own slots
+        let own = super::vtable_index_for(&index, ns, "IRandomAccessStream", "get_Size").unwrap();
+        assert_eq!(own, ((ns.to_string(), "IRandomAccessStream".to_string()), 6));
+        let read = super::vtable_index_for(&index, ns, "IRandomAccessStream", "ReadAsync").unwrap();
+        assert_eq!(read, ((ns.to_string(), "IInputStream".to_string()), 6));
+        let close = super::vtable_index_for(&index, ns, "IRandomAccessStream", "Close").unwrap();
+        assert_eq!(close, (("Windows.Foundation".to_string(), "IClosable".to_string()), 6));
+        assert!(super::vtable_index_for(&index, ns, "IRandomAccessStream", "Nope").is_err());
+    }
+
     #[test]
     fn verify_typed_event_handler_iid() {
         use crate::metadata_table::*;
@@ -213,4 +611,25 @@ mod tests {
         >::IID;
         assert_eq!(p.iid().unwrap(), expected);
     }
+
+    #[test]
+    fn typed_event_handler_delegate_iid() {
+        use crate::metadata_table::*;
+        use windows::Foundation::{IStringable, TypedEventHandler, Uri};
+        use windows_core::{HSTRING, IInspectable, Interface};
+
+        let table = MetadataTable::new();
+        let uri = table.runtime_class("Windows.Foundation.Uri".into(), Uri::IID);
+        let handler = table.typed_event_handler(&uri, &table.interface(IStringable::IID));
+        assert_eq!(handler.delegate_iid().unwrap(), TypedEventHandler::<Uri, IStringable>::IID);
+
+        let handler = table.typed_event_handler(&table.object(), &table.hstring());
+        assert_eq!(handler.delegate_iid().unwrap(), TypedEventHandler::<IInspectable, HSTRING>::IID);
+
+        // A non-generic delegate is its own IID
+        assert_eq!(table.delegate(IStringable::IID).delegate_iid(), Some(IStringable::IID));
+        // Interfaces, even parameterized ones, are not delegates
+        assert_eq!(table.reference(&table.i32_type()).delegate_iid(), None);
+        assert_eq!(table.interface(IStringable::IID).delegate_iid(), None);
+    }
 }
@@ -31,7 +31,9 @@ pub(super) struct StructEntry {
 
 pub(super) struct EnumData {
     pub(super) name: String,
-    pub(super) members: Vec<(String, i32)>,
+    /// Underlying integer kind; determines ABI width and signature.
+    pub(super) underlying: TypeKind,
+    pub(super) members: Vec<(String, i64)>,
 }
 
 pub(super) struct InterfaceMethodTable {
@@ -91,11 +93,12 @@ impl MetadataTable {
         id
     }
 
-    pub(super) fn push_enum(&self, name: &str, members: Vec<(String, i32)>) -> u32 {
+    pub(super) fn push_enum(&self, name: &str, underlying: TypeKind, members: Vec<(String, i64)>) -> u32 {
         let mut enums = self.enum_entries.write().unwrap();
         let id = enums.len() as u32;
         enums.push(EnumData {
             name: name.to_string(),
+            underlying,
             members,
         });
         id
@@ -150,6 +153,11 @@ impl MetadataTable {
         self.type_names.write().unwrap().insert(name.to_string(), kind);
     }
 
+    /// Reverse lookup of a registered name; a linear scan, for diagnostics.
+    pub(crate) fn name_of_kind(&self, kind: TypeKind) -> Option<String> {
+        self.type_names.read().unwrap().iter().find(|(_, k)| **k == kind).map(|(n, _)| n.clone())
+    }
+
     // -----------------------------------------------------------------------
     // Arena read operations
     // -----------------------------------------------------------------------
@@ -178,7 +186,11 @@ impl MetadataTable {
         self.enum_entries.read().unwrap()[idx as usize].name.clone()
     }
 
-    pub(crate) fn get_enum_member_name(&self, idx: u32, value: i32) -> Option<String> {
+    pub(crate) fn get_enum_underlying(&self, idx: u32) -> TypeKind {
+        self.enum_entries.read().unwrap()[idx as usize].underlying
+    }
+
+    pub(crate) fn get_enum_member_name(&self, idx: u32, value: i64) -> Option<String> {
         let enums = self.enum_entries.read().unwrap();
         let entry = &enums[idx as usize];
         entry.members.iter()
@@ -186,7 +198,7 @@ impl MetadataTable {
             .map(|(n, _)| n.clone())
     }
 
-    pub(super) fn get_enum_members(&self, enum_name: &str) -> Option<Vec<(String, i32)>> {
+    pub(super) fn get_enum_members(&self, enum_name: &str) -> Option<Vec<(String, i64)>> {
         let enums = self.enum_entries.read().unwrap();
         enums.iter()
             .find(|e| e.name == enum_name)
@@ -234,6 +246,9 @@ impl MetadataTable {
     // -----------------------------------------------------------------------
 
     pub(crate) fn size_of_kind(&self, kind: TypeKind) -> usize {
+        if let TypeKind::Enum(idx) = kind {
+            return self.size_of_kind(self.get_enum_underlying(idx));
+        }
         if let Some(s) = kind.primitive_size() {
             return s;
         }
@@ -252,6 +267,9 @@ impl MetadataTable {
     }
 
     pub(crate) fn align_of_kind(&self, kind: TypeKind) -> usize {
+        if let TypeKind::Enum(idx) = kind {
+            return self.align_of_kind(self.get_enum_underlying(idx));
+        }
         if let Some(a) = kind.primitive_align() {
             return a;
         }
@@ -272,7 +290,9 @@ impl MetadataTable {
     pub(crate) fn layout_of_kind(&self, kind: TypeKind) -> Layout {
         let size = self.size_of_kind(kind);
         let align = self.align_of_kind(kind);
-        Layout::from_size_align(size, align).unwrap()
+        Layout::from_size_align(size, align).unwrap_or_else(|e| {
+            panic!("invalid layout for {:?} (size {}, align {}): {}", kind, size, align, e)
+        })
     }
 
     pub(crate) fn field_count_kind(&self, kind: TypeKind) -> usize {
@@ -296,38 +316,21 @@ impl MetadataTable {
         }
     }
 
-    pub(crate) fn libffi_type_kind(&self, kind: TypeKind) -> libffi::middle::Type {
-        if let Some(t) = kind.primitive_libffi_type() {
-            return t;
-        }
-        match kind {
-            TypeKind::Struct(id) => {
-                let structs = self.structs.read().unwrap();
-                let field_types: Vec<libffi::middle::Type> = structs[id as usize]
-                    .field_kinds
-                    .iter()
-                    .map(|f| self.libffi_type_kind(*f))
-                    .collect();
-                libffi::middle::Type::structure(field_types)
-            }
-            // Pointer-sized types (COM objects, HString handle, etc.)
-            TypeKind::HString | TypeKind::Object | TypeKind::Interface(_)
-            | TypeKind::Delegate(_) | TypeKind::RuntimeClass(_)
-            | TypeKind::Parameterized(_)
-            | TypeKind::IAsyncAction | TypeKind::IAsyncActionWithProgress(_)
-            | TypeKind::IAsyncOperation(_) | TypeKind::IAsyncOperationWithProgress(_)
-            | TypeKind::OutValue(_) | TypeKind::ArrayOfIUnknown => libffi::middle::Type::pointer(),
-            _ => panic!("libffi_type_kind: unsupported for {:?}", kind),
-        }
-    }
-
+    /// Field offsets and overall layout, C rules. Panics if the struct would be
+    /// larger than `isize::MAX` (`Layout::extend` checks every step), rather
+    /// than wrapping to a small allocation that field writes overrun.
     pub(super) fn compute_layout(&self, fields: &[TypeKind]) -> (Vec<usize>, Layout) {
         let mut offsets = Vec::with_capacity(fields.len());
-        let mut layout = Layout::from_size_align(0, 1).unwrap();
+        let mut layout = Layout::new::<()>();
 
-        for field in fields {
+        for (i, field) in fields.iter().enumerate() {
             let field_layout = self.layout_of_kind(*field);
-            let (new_layout, offset) = layout.extend(field_layout).unwrap();
+            let (new_layout, offset) = layout.extend(field_layout).unwrap_or_else(|_| {
+                panic!(
+                    "struct layout overflows at field {} ({:?}): {} + {} bytes exceeds isize::MAX",
+                    i, field, layout.size(), field_layout.size()
+                )
+            });
             offsets.push(offset);
             layout = new_layout;
         }
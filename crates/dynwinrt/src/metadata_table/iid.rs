@@ -36,7 +36,21 @@ impl MetadataTable {
         }
     }
 
+    /// Signature string for `kind`, memoized in `signature_cache`.
     pub(crate) fn signature_string_kind(&self, kind: TypeKind) -> String {
+        if let Some(sig) = kind.signature() {
+            return sig.into();
+        }
+        if let Some(sig) = self.signature_cache.read().unwrap().get(&kind) {
+            return sig.clone();
+        }
+        let sig = self.compute_signature_string_kind(kind);
+        self.signature_cache.write().unwrap().insert(kind, sig.clone());
+        sig
+    }
+
+    /// Uncached signature computation. Nested kinds still go through the cache.
+    pub(crate) fn compute_signature_string_kind(&self, kind: TypeKind) -> String {
         if let Some(sig) = kind.signature() {
             return sig.into();
         }
@@ -70,10 +84,12 @@ impl MetadataTable {
                 self.pinterface_signature(&IASYNC_OPERATION_WITH_PROGRESS, &self.async_type_args(kind))
             }
             TypeKind::Object => "cinterface(IInspectable)".to_string(),
-            TypeKind::HResult => "i4".to_string(),
+            // HRESULT is projected as the Windows.Foundation.HResult struct
+            TypeKind::HResult => "struct(Windows.Foundation.HResult;i4)".to_string(),
             TypeKind::Enum(idx) => {
                 let name = self.get_enum_name(idx);
-                format!("enum({};i4)", name)
+                let underlying = self.signature_string_kind(self.get_enum_underlying(idx));
+                format!("enum({};{})", name, underlying)
             }
             TypeKind::Struct(idx) => {
                 let entry = &self.structs.read().unwrap()[idx as usize];
@@ -99,10 +115,11 @@ impl MetadataTable {
             | TypeKind::IAsyncActionWithProgress(_)
             | TypeKind::IAsyncOperation(_)
             | TypeKind::IAsyncOperationWithProgress(_) => {
-                Some(self.compute_parameterized_iid(
-                    &self.parameterized_piid(kind),
-                    &self.parameterized_type_args(kind),
-                ))
+                // The full pinterface signature is cached, so repeated iid() calls
+                // on the same kind skip the recursive string build.
+                let sig = self.signature_string_kind(kind);
+                let buf = windows_core::imp::ConstBuffer::from_slice(sig.as_bytes());
+                Some(GUID::from_signature(buf))
             }
             _ => None,
         }
@@ -138,30 +155,4 @@ impl MetadataTable {
         };
         Some(self.compute_parameterized_iid(&handler_piid, &progress_args))
     }
-
-    fn parameterized_piid(&self, kind: TypeKind) -> GUID {
-        match kind {
-            TypeKind::Parameterized(idx) => {
-                let (generic_def, _) = self.get_parameterized(idx);
-                match generic_def {
-                    TypeKind::Generic { piid, .. } | TypeKind::Interface(piid) => piid,
-                    _ => panic!("Parameterized base must be Generic or Interface"),
-                }
-            }
-            TypeKind::IAsyncActionWithProgress(_) => IASYNC_ACTION_WITH_PROGRESS,
-            TypeKind::IAsyncOperation(_) => IASYNC_OPERATION,
-            TypeKind::IAsyncOperationWithProgress(_) => IASYNC_OPERATION_WITH_PROGRESS,
-            _ => panic!("Not a parameterized type: {:?}", kind),
-        }
-    }
-
-    fn parameterized_type_args(&self, kind: TypeKind) -> Vec<TypeKind> {
-        match kind {
-            TypeKind::Parameterized(idx) => {
-                let (_, args) = self.get_parameterized(idx);
-                args
-            }
-            _ => self.async_type_args(kind),
-        }
-    }
 }
@@ -11,6 +11,7 @@ pub use value_data::ValueTypeData;
 pub use method_handle::MethodHandle;
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
 use windows_core::GUID;
@@ -45,6 +46,15 @@ pub struct MetadataTable {
     interface_methods: RwLock<HashMap<GUID, InterfaceMethodTable>>,
     /// Name → TypeKind for dedup of all named types (struct, enum, runtime_class).
     type_names: RwLock<HashMap<String, TypeKind>>,
+
+    // --- Caches ---
+    /// TypeKind → WinRT signature string. Arenas are append-only, so a kind's
+    /// signature never changes once computed.
+    signature_cache: RwLock<HashMap<TypeKind, String>>,
+
+    // --- Options ---
+    /// QI `Interface(iid)` out params to `iid`; see `set_requery_interface_outs`.
+    requery_interface_outs: AtomicBool,
 }
 
 impl std::fmt::Debug for MetadataTable {
@@ -71,9 +81,23 @@ impl MetadataTable {
             methods: RwLock::new(Vec::new()),
             interface_methods: RwLock::new(HashMap::new()),
             type_names: RwLock::new(HashMap::new()),
+            signature_cache: RwLock::new(HashMap::new()),
+            requery_interface_outs: AtomicBool::new(false),
         })
     }
 
+    /// When on, an out param typed `Interface(iid)` is QI'd to `iid` before it
+    /// is returned, so the object can be called without a further `cast`.
+    /// Off by default: most servers already hand back that interface, and the
+    /// QI is then an identity round trip.
+    pub fn set_requery_interface_outs(&self, on: bool) {
+        self.requery_interface_outs.store(on, Ordering::Relaxed);
+    }
+
+    pub fn requery_interface_outs(&self) -> bool {
+        self.requery_interface_outs.load(Ordering::Relaxed)
+    }
+
     // -----------------------------------------------------------------------
     // Type factory methods
     // -----------------------------------------------------------------------
@@ -114,6 +138,14 @@ impl MetadataTable {
     pub fn interface(self: &Arc<Self>, iid: GUID) -> TypeHandle {
         self.make(TypeKind::Interface(iid))
     }
+    /// `interface(iid)` with `name` recorded for diagnostics. The signature
+    /// stays the braced IID, which is what WinRT uses for non-parameterized
+    /// interfaces as type arguments; only classes embed their name (`rc(...)`).
+    pub fn named_interface(self: &Arc<Self>, name: &str, iid: GUID) -> TypeHandle {
+        let kind = TypeKind::Interface(iid);
+        self.insert_named_type(name, kind);
+        self.make(kind)
+    }
     pub fn delegate(self: &Arc<Self>, iid: GUID) -> TypeHandle {
         self.make(TypeKind::Delegate(iid))
     }
@@ -136,6 +168,34 @@ impl MetadataTable {
         self.make(self.push_parameterized(generic_def.kind, args_kinds))
     }
 
+    // Well-known parameterized interfaces, PIID and arity filled in
+    pub fn vector(self: &Arc<Self>, element_type: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IVECTOR, 1), std::slice::from_ref(element_type))
+    }
+    pub fn vector_view(self: &Arc<Self>, element_type: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IVECTOR_VIEW, 1), std::slice::from_ref(element_type))
+    }
+    pub fn iterable(self: &Arc<Self>, element_type: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IITERABLE, 1), std::slice::from_ref(element_type))
+    }
+    pub fn map(self: &Arc<Self>, key_type: &TypeHandle, value_type: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IMAP, 2), &[key_type.clone(), value_type.clone()])
+    }
+    pub fn map_view(self: &Arc<Self>, key_type: &TypeHandle, value_type: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IMAP_VIEW, 2), &[key_type.clone(), value_type.clone()])
+    }
+    pub fn key_value_pair(self: &Arc<Self>, key_type: &TypeHandle, value_type: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IKEY_VALUE_PAIR, 2), &[key_type.clone(), value_type.clone()])
+    }
+    pub fn reference(self: &Arc<Self>, inner: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(IREFERENCE, 1), std::slice::from_ref(inner))
+    }
+
+    /// `TypedEventHandler<sender, args>`.
+    pub fn typed_event_handler(self: &Arc<Self>, sender: &TypeHandle, args: &TypeHandle) -> TypeHandle {
+        self.parameterized(&self.generic(TYPED_EVENT_HANDLER, 2), &[sender.clone(), args.clone()])
+    }
+
     pub fn async_operation(self: &Arc<Self>, result_type: &TypeHandle) -> TypeHandle {
         let idx = self.push_inner_type(result_type.kind);
         self.make(TypeKind::IAsyncOperation(idx))
@@ -194,12 +254,31 @@ impl MetadataTable {
         self.make(kind)
     }
 
-    /// Register a named enum with member values.
+    /// Register a named enum with member values (Int32 underlying type).
     pub fn enum_type(self: &Arc<Self>, name: &str, members: Vec<(String, i32)>) -> TypeHandle {
+        let members = members.into_iter().map(|(n, v)| (n, v as i64)).collect();
+        self.enum_type_with_underlying(name, &self.i32_type(), members)
+    }
+
+    /// Register a named enum whose underlying integer type is not Int32
+    /// (e.g. `UInt32` flags, or `u8`/`i64`-backed enums). The ABI width,
+    /// layout and signature all follow `underlying`.
+    pub fn enum_type_with_underlying(
+        self: &Arc<Self>,
+        name: &str,
+        underlying: &TypeHandle,
+        members: Vec<(String, i64)>,
+    ) -> TypeHandle {
+        assert!(
+            matches!(underlying.kind(),
+                TypeKind::I8 | TypeKind::U8 | TypeKind::I16 | TypeKind::U16
+                | TypeKind::I32 | TypeKind::U32 | TypeKind::I64 | TypeKind::U64),
+            "enum underlying type must be an integer, got {:?}", underlying.kind()
+        );
         if let Some(kind) = self.get_named_type(name) {
             return self.make(kind);
         }
-        let id = self.push_enum(name, members);
+        let id = self.push_enum(name, underlying.kind(), members);
         let kind = TypeKind::Enum(id);
         self.insert_named_type(name, kind);
         self.make(kind)
@@ -230,7 +309,7 @@ impl MetadataTable {
     // Query API
     // -----------------------------------------------------------------------
 
-    pub fn get_enum_value(&self, enum_name: &str, member_name: &str) -> Option<i32> {
+    pub fn get_enum_value(&self, enum_name: &str, member_name: &str) -> Option<i64> {
         self.get_enum_members(enum_name)?
             .iter()
             .find(|(n, _)| n == member_name)
@@ -305,6 +384,73 @@ mod tests {
         assert_eq!(table.f64_type().align_of(), 8);
     }
 
+    #[test]
+    fn owned_and_borrowed_out_pointers_balance_refcounts() {
+        use windows::Foundation::Uri;
+        use windows_core::{IUnknown, h};
+
+        // AddRef + Release round trip: Release returns the count before our AddRef
+        fn refs(obj: &IUnknown) -> u32 {
+            unsafe {
+                (obj.vtable().AddRef)(obj.as_raw());
+                (obj.vtable().Release)(obj.as_raw())
+            }
+        }
+
+        let table = MetadataTable::new();
+        let uri: IUnknown = Uri::CreateUri(h!("https://www.example.com")).unwrap().cast().unwrap();
+        let base = refs(&uri);
+
+        // Out param: the callee's +1 is adopted, not AddRef'd again
+        let owned = table.object().from_out(uri.clone().into_raw()).unwrap();
+        assert_eq!(refs(&uri), base + 1);
+        drop(owned);
+        assert_eq!(refs(&uri), base);
+
+        // Borrowed pointer: wrapping takes its own reference
+        let borrowed = table.object().from_out_borrowed(uri.as_raw()).unwrap();
+        assert_eq!(refs(&uri), base + 1);
+        let copy = borrowed.clone();
+        assert_eq!(refs(&uri), base + 2);
+        drop(copy);
+        drop(borrowed);
+        assert_eq!(refs(&uri), base);
+
+        assert_eq!(table.object().from_out_borrowed(std::ptr::null_mut()).unwrap(), WinRTValue::Null);
+        let s = windows_core::HSTRING::from("lent");
+        let raw: *mut std::ffi::c_void = unsafe { std::mem::transmute_copy(&s) };
+        assert_eq!(table.hstring().from_out_borrowed(raw).unwrap(), WinRTValue::HString(s.clone()));
+    }
+
+    #[test]
+    fn hstring_outs_adopt_the_handle() {
+        use windows::Foundation::Uri;
+        use windows_core::{HSTRING, h};
+        use crate::abi::AbiValue;
+
+        let table = MetadataTable::new();
+        // Each out is a +1 on a shared string; adopting it must not free the
+        // original, and dropping the value must not leak or double free.
+        let source = HSTRING::from("dynamic out");
+        for _ in 0..1000 {
+            let raw: *mut std::ffi::c_void = unsafe { std::mem::transmute(source.clone()) };
+            assert_eq!(table.hstring().from_out(raw).unwrap(), WinRTValue::HString(source.clone()));
+            let raw: *mut std::ffi::c_void = unsafe { std::mem::transmute(source.clone()) };
+            let value = table.hstring().from_out_value(&AbiValue::Pointer(raw)).unwrap();
+            assert_eq!(value.as_hstring().unwrap(), "dynamic out");
+        }
+        assert_eq!(source, "dynamic out");
+        assert_eq!(table.hstring().from_out(std::ptr::null_mut()).unwrap(), WinRTValue::HString(HSTRING::new()));
+
+        // A string allocated by the OS, through the dynamic getter path
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let get_absolute_uri = &crate::interfaces::uri_vtable(&table).methods[6];
+        for _ in 0..1000 {
+            let out = get_absolute_uri.call_dynamic(uri.as_raw(), &[]).unwrap();
+            assert_eq!(out[0].as_hstring().unwrap(), "https://www.example.com/path");
+        }
+    }
+
     #[test]
     fn abi_type_mapping() {
         let table = MetadataTable::new();
@@ -358,6 +504,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "dynamic-call")]
     fn struct_nested_libffi_type() {
         let table = MetadataTable::new();
         let f32_h = table.f32_type();
@@ -369,6 +516,36 @@ mod tests {
         let _ = outer.libffi_type(); // nested struct should work
     }
 
+    #[test]
+    fn struct_nested_field_path_access() {
+        let table = MetadataTable::new();
+        let f32_h = table.f32_type();
+        let f64_h = table.f64_type();
+        let point = table.struct_type("Windows.Foundation.Point", &[f32_h.clone(), f32_h]);
+        let outer = table.struct_type("Test.AltitudeAndPoint", &[f64_h, point]);
+
+        let mut val = outer.default_value();
+        val.set_field_path(&[0], 100.0f64);
+        val.set_field_path(&[1, 1], 2.5f32);
+        assert_eq!(val.get_field_path::<f64>(&[0]), 100.0);
+        assert_eq!(val.get_field_path::<f32>(&[1, 0]), 0.0);
+        assert_eq!(val.get_field_path::<f32>(&[1, 1]), 2.5);
+
+        // Same bytes as going through the nested struct copy
+        assert_eq!(val.get_field_struct(1).get_field::<f32>(1), 2.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "struct layout overflows")]
+    fn struct_layout_overflow_panics() {
+        // Each level doubles the size: 8 << 61 passes isize::MAX
+        let table = MetadataTable::new();
+        let mut s = table.f64_type();
+        for i in 0..64 {
+            s = table.struct_type(&format!("Test.Doubling{}", i), &[s.clone(), s]);
+        }
+    }
+
     #[test]
     fn struct_dedup_by_name() {
         let table = MetadataTable::new();
@@ -405,6 +582,53 @@ mod tests {
         assert_eq!(table.get_enum_value("Nonexistent.Enum", "Foo"), None);
     }
 
+    #[test]
+    fn enum_abi_follows_underlying_width() {
+        let table = MetadataTable::new();
+        let e8 = table.enum_type_with_underlying("Test.ByteEnum", &table.u8_type(), vec![
+            ("A".into(), 1),
+            ("B".into(), 0xFF),
+        ]);
+        let e32 = table.enum_type("Test.IntEnum", vec![("Neg".into(), -1)]);
+        let e64 = table.enum_type_with_underlying("Test.WideFlags", &table.i64_type(), vec![
+            ("High".into(), 1 << 40),
+        ]);
+
+        assert_eq!((e8.abi_type(), e8.size_of(), e8.align_of()), (AbiType::U8, 1, 1));
+        assert_eq!((e32.abi_type(), e32.size_of(), e32.align_of()), (AbiType::I32, 4, 4));
+        assert_eq!((e64.abi_type(), e64.size_of(), e64.align_of()), (AbiType::I64, 8, 8));
+        assert_eq!(e8.signature_string(), "enum(Test.ByteEnum;u1)");
+        assert_eq!(e32.signature_string(), "enum(Test.IntEnum;i4)");
+        assert_eq!(e64.signature_string(), "enum(Test.WideFlags;i8)");
+
+        // Out-param reads only the underlying width; adjacent bytes are garbage.
+        let buf: [u8; 8] = [0xFF, 0xAB, 0xCD, 0xEF, 0x12, 0x34, 0x56, 0x78];
+        let v8 = e8.from_out(buf.as_ptr() as *mut _).unwrap();
+        assert!(matches!(v8, WinRTValue::Enum { value: 0xFF, .. }));
+        assert_eq!(e8.enum_member_name(0xFF).as_deref(), Some("B"));
+
+        let neg = (-1i32).to_ne_bytes();
+        let mut buf32 = [0xAAu8; 8];
+        buf32[..4].copy_from_slice(&neg);
+        let v32 = e32.from_out(buf32.as_mut_ptr() as *mut _).unwrap();
+        assert!(matches!(v32, WinRTValue::Enum { value: -1, .. }));
+
+        let wide = (1i64 << 40).to_ne_bytes();
+        let v64 = e64.from_out(wide.as_ptr() as *mut _).unwrap();
+        assert!(matches!(v64, WinRTValue::Enum { value, .. } if value == 1 << 40));
+        assert_eq!(table.get_enum_value("Test.WideFlags", "High"), Some(1 << 40));
+
+        // Writing through out_ptr() then finish_out() sign-extends narrower enums.
+        let mut out = e32.default_winrt_value();
+        unsafe { *(out.out_ptr() as *mut i32) = -1 };
+        out.finish_out();
+        assert!(matches!(out, WinRTValue::Enum { value: -1, .. }));
+
+        // A struct with a u8 enum field packs it as one byte.
+        let s = table.struct_type("Test.Packed", &[e8.clone(), e8.clone()]);
+        assert_eq!(s.size_of(), 2);
+    }
+
     // -----------------------------------------------------------------------
     // Interface: registration, method lookup
     // -----------------------------------------------------------------------
@@ -453,12 +677,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn iid_interface_as_type_arg() {
+        use windows::Foundation::IStringable;
+
+        let table = MetadataTable::new();
+        let plain = table.interface(IStringable::IID);
+        let named = table.named_interface("Windows.Foundation.IStringable", IStringable::IID);
+        assert_eq!(named.signature_string(), "{96369f54-8eb6-48f0-abce-c1b211e627c3}");
+        assert_eq!(named.signature_string(), plain.signature_string());
+        assert_eq!(named.interface_name().as_deref(), Some("Windows.Foundation.IStringable"));
+        assert_eq!(table.interface(GUID::from_u128(1)).interface_name(), None);
+
+        // Must match windows-rs for IAsyncOperation<IStringable>
+        let op = table.async_operation(&named);
+        assert_eq!(op.iid().unwrap(), windows_future::IAsyncOperation::<IStringable>::IID);
+        let g = table.generic(IASYNC_OPERATION, 1);
+        assert_eq!(table.parameterized(&g, &[plain]).iid(), op.iid());
+    }
+
     #[test]
     fn iid_runtime_class_as_type_arg() {
         let table = MetadataTable::new();
         let storage_file = table.runtime_class(
             "Windows.Storage.StorageFile".into(),
-            GUID::from_u128(0xFA3F6186_4214_428C_A64C_14C9AC7315EA),
+            windows::Storage::IStorageFile::IID,
         );
         let g = table.generic(IASYNC_OPERATION, 1);
         let ty = table.parameterized(&g, &[storage_file]);
@@ -481,6 +724,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn signature_string_cache_nested_vector() {
+        let table = MetadataTable::new();
+        let inner = table.vector(&table.hstring());
+        let outer = table.vector(&inner);
+
+        let fresh = table.compute_signature_string_kind(outer.kind());
+        assert_eq!(outer.signature_string(), fresh);
+        // Second call is served from the cache and must be byte-identical
+        assert_eq!(outer.signature_string(), fresh);
+
+        let iid = outer.iid().unwrap();
+        assert_eq!(outer.iid().unwrap(), iid);
+        assert_eq!(
+            iid,
+            windows_collections::IVector::<windows_collections::IVector<windows_core::HSTRING>>::IID,
+        );
+    }
+
+    #[test]
+    fn well_known_parameterized_constructors() {
+        use windows_collections::{IIterable, IMap, IMapView, IVector, IVectorView};
+        use windows_core::{HSTRING, IInspectable};
+
+        let table = MetadataTable::new();
+        let s = table.hstring();
+        assert_eq!(table.vector(&s).iid().unwrap(), IVector::<HSTRING>::IID);
+        assert_eq!(table.vector_view(&s).iid().unwrap(), IVectorView::<HSTRING>::IID);
+        assert_eq!(table.iterable(&s).iid().unwrap(), IIterable::<HSTRING>::IID);
+        assert_eq!(table.map(&s, &table.object()).iid().unwrap(), IMap::<HSTRING, IInspectable>::IID);
+        assert_eq!(table.map_view(&s, &table.i32_type()).iid().unwrap(), IMapView::<HSTRING, i32>::IID);
+        assert_eq!(table.reference(&table.i32_type()).iid().unwrap(), windows::Foundation::IReference::<i32>::IID);
+
+        // Same as the long-hand form
+        let long = table.parameterized(&table.generic(IVECTOR, 1), &[s.clone()]);
+        assert_eq!(long.signature_string(), table.vector(&s).signature_string());
+    }
+
     #[test]
     fn guid_braced_format() {
         let guid = GUID::from_u128(0x9fc2b0bb_e446_44e2_aa61_9cab8f636af2);
@@ -492,6 +773,7 @@ mod tests {
     // -----------------------------------------------------------------------
 
     #[test]
+    #[cfg(feature = "dynamic-call")]
     fn e2e_uri_create_and_query() {
         use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
         use windows_core::{Interface, h};
@@ -550,6 +832,27 @@ mod tests {
     }
 
     #[test]
+    fn value_type_args_compute_parameterized_iids() {
+        use windows::Devices::Geolocation::BasicGeoposition;
+
+        let table = MetadataTable::new();
+        let f64_h = table.f64_type();
+        let geo = table.struct_type(
+            "Windows.Devices.Geolocation.BasicGeoposition",
+            &[f64_h.clone(), f64_h.clone(), f64_h],
+        );
+        assert_eq!(geo.signature_string(), "struct(Windows.Devices.Geolocation.BasicGeoposition;f8;f8;f8)");
+        assert_eq!(
+            table.async_operation(&geo).iid().unwrap(),
+            windows_future::IAsyncOperation::<BasicGeoposition>::IID,
+        );
+
+        let hr = table.reference(&table.hresult());
+        assert_eq!(hr.iid().unwrap(), windows::Foundation::IReference::<windows_core::HRESULT>::IID);
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
     fn e2e_geopoint_struct_in_param() -> windows::core::Result<()> {
         use windows::Devices::Geolocation::{Geopoint, IGeopointFactory};
         use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
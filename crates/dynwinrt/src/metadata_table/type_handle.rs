@@ -136,7 +136,8 @@ impl TypeHandle {
             TypeKind::U8 => AbiType::U8,
             TypeKind::I16 => AbiType::I16,
             TypeKind::U16 | TypeKind::Char16 => AbiType::U16,
-            TypeKind::I32 | TypeKind::HResult | TypeKind::Enum(_) => AbiType::I32,
+            TypeKind::I32 | TypeKind::HResult => AbiType::I32,
+            TypeKind::Enum(_) => self.enum_underlying().abi_type(),
             TypeKind::U32 => AbiType::U32,
             TypeKind::I64 => AbiType::I64,
             TypeKind::U64 => AbiType::U64,
@@ -154,19 +155,34 @@ impl TypeHandle {
                 panic!("Cannot get ABI type for uninstantiated Generic({:?})", piid)
             }
 
-            TypeKind::Struct(_) => {
-                panic!("Struct types do not have a simple AbiType; use libffi_type() instead")
-            }
+            TypeKind::Struct(_) => AbiType::Struct(
+                (0..self.field_count())
+                    .map(|i| {
+                        let field = self.field_type(i);
+                        match field.kind {
+                            // Embedded by value, not through a pointer as when passed alone
+                            TypeKind::Guid => AbiType::Struct(vec![
+                                AbiType::U32, AbiType::U16, AbiType::U16,
+                                AbiType::U8, AbiType::U8, AbiType::U8, AbiType::U8,
+                                AbiType::U8, AbiType::U8, AbiType::U8, AbiType::U8,
+                            ]),
+                            _ => field.abi_type(),
+                        }
+                    })
+                    .collect(),
+            ),
 
             TypeKind::Array(_) => {
                 panic!("Array types expand to multiple ABI parameters; cannot map to single AbiType")
             }
+
+            TypeKind::Unit => panic!("Unit is a void result and has no ABI type"),
         }
     }
 
+    #[cfg(feature = "dynamic-call")]
     pub fn libffi_type(&self) -> libffi::middle::Type {
         match self.kind {
-            TypeKind::Struct(_) => self.table.libffi_type_kind(self.kind),
             TypeKind::Array(_) => {
                 panic!("Array types expand to multiple libffi types")
             }
@@ -188,15 +204,57 @@ impl TypeHandle {
         }
     }
 
-    /// Reverse-lookup an enum member name from its i32 value.
+    /// Reverse-lookup an enum member name from its value.
     /// Returns None if not an Enum type or no member matches.
-    pub fn enum_member_name(&self, value: i32) -> Option<String> {
+    pub fn enum_member_name(&self, value: i64) -> Option<String> {
         match self.kind {
             TypeKind::Enum(idx) => self.table.get_enum_member_name(idx, value),
             _ => None,
         }
     }
 
+    /// Underlying integer type of an Enum (Int32 unless registered otherwise).
+    pub fn enum_underlying(&self) -> TypeHandle {
+        match self.kind {
+            TypeKind::Enum(idx) => TypeHandle {
+                table: Arc::clone(&self.table),
+                kind: self.table.get_enum_underlying(idx),
+            },
+            _ => panic!("enum_underlying called on non-enum type {:?}", self.kind),
+        }
+    }
+
+    /// Build an Enum value from raw bits, truncating and sign/zero-extending
+    /// according to the underlying width so stray high bytes are never observed.
+    pub(crate) fn enum_from_bits(&self, bits: i64) -> WinRTValue {
+        let value = match self.enum_underlying().kind {
+            TypeKind::I8 => bits as i8 as i64,
+            TypeKind::U8 => bits as u8 as i64,
+            TypeKind::I16 => bits as i16 as i64,
+            TypeKind::U16 => bits as u16 as i64,
+            TypeKind::I32 => bits as i32 as i64,
+            TypeKind::U32 => bits as u32 as i64,
+            _ => bits,
+        };
+        WinRTValue::Enum { value, type_handle: self.clone() }
+    }
+
+    /// Read an Enum value of the underlying width from `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must point to at least `size_of()` readable bytes.
+    pub(crate) unsafe fn read_enum(&self, ptr: *const u8) -> WinRTValue {
+        let bits = unsafe {
+            match self.size_of() {
+                1 => *ptr as i64,
+                2 => (ptr as *const u16).read_unaligned() as i64,
+                4 => (ptr as *const u32).read_unaligned() as i64,
+                _ => (ptr as *const i64).read_unaligned(),
+            }
+        };
+        self.enum_from_bits(bits)
+    }
+
     pub fn signature_string(&self) -> String {
         self.table.signature_string_kind(self.kind)
     }
@@ -205,6 +263,26 @@ impl TypeHandle {
         self.table.iid_kind(self.kind)
     }
 
+    /// Name an `Interface` type was registered under with `named_interface`
+    /// (or loaded from winmd). `None` for anonymous interfaces and other kinds.
+    pub fn interface_name(&self) -> Option<String> {
+        match self.kind {
+            TypeKind::Interface(_) => self.table.name_of_kind(self.kind),
+            _ => None,
+        }
+    }
+
+    /// IID a callback object of this delegate type must answer QI for: the
+    /// delegate's own IID, computed from its type args when parameterized
+    /// (e.g. `TypedEventHandler<Sender, Args>`). `None` for non-delegates.
+    pub fn delegate_iid(&self) -> Option<GUID> {
+        match self.kind {
+            TypeKind::Delegate(iid) => Some(iid),
+            TypeKind::Parameterized(idx) if is_delegate_piid(self.table.get_parameterized(idx).0) => self.iid(),
+            _ => None,
+        }
+    }
+
     pub fn completed_handler_iid(&self) -> Option<GUID> {
         self.table.completed_handler_iid_kind(self.kind)
     }
@@ -213,6 +291,17 @@ impl TypeHandle {
         self.table.progress_handler_iid_kind(self.kind)
     }
 
+    /// Type arguments of a Parameterized type (empty for anything else).
+    pub fn generic_args(&self) -> Vec<TypeHandle> {
+        match self.kind {
+            TypeKind::Parameterized(idx) => self.table.get_parameterized(idx).1
+                .into_iter()
+                .map(|kind| TypeHandle { table: Arc::clone(&self.table), kind })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn is_array(&self) -> bool {
         matches!(self.kind, TypeKind::Array(_))
     }
@@ -227,6 +316,53 @@ impl TypeHandle {
         }
     }
 
+    /// Inner type of an `OutValue(inner)` type.
+    pub fn out_value_inner(&self) -> TypeHandle {
+        match self.kind {
+            TypeKind::OutValue(idx) => TypeHandle {
+                table: Arc::clone(&self.table),
+                kind: self.table.get_inner_type(idx),
+            },
+            _ => panic!("out_value_inner called on non-OutValue type {:?}", self.kind),
+        }
+    }
+
+    /// For an `OutValue(inner)` type: a zeroed CoTaskMem slot sized for `inner`,
+    /// wrapped as `WinRTValue::OutValue`. Pass it where the ABI takes a `T*`,
+    /// then `WinRTValue::decode_out` reads the result. The slot is freed, and
+    /// anything left in it released, when the value drops.
+    pub fn alloc_out_value(&self) -> WinRTValue {
+        let inner = self.out_value_inner();
+        let size = inner.element_size();
+        let ptr = unsafe { windows::Win32::System::Com::CoTaskMemAlloc(size) };
+        assert!(!ptr.is_null(), "CoTaskMemAlloc failed for OutValue");
+        unsafe { std::ptr::write_bytes(ptr as *mut u8, 0, size) };
+        WinRTValue::OutValue(crate::value::OutSlot::new(ptr, inner), self.clone())
+    }
+
+    /// Read a value of this type from an out slot the callee has written,
+    /// taking ownership of any HSTRING or COM reference in it.
+    pub(crate) unsafe fn read_out_slot(&self, ptr: *mut std::ffi::c_void) -> crate::result::Result<WinRTValue> {
+        let mut value = match self.kind {
+            TypeKind::Struct(_) => {
+                let mut data = self.default_value();
+                unsafe { std::ptr::copy_nonoverlapping(ptr as *const u8, data.as_mut_ptr(), self.element_size()) };
+                return Ok(WinRTValue::Struct(data));
+            }
+            TypeKind::Guid => return Ok(WinRTValue::Guid(unsafe { *(ptr as *const GUID) })),
+            _ if matches!(self.abi_type(), AbiType::Ptr) => {
+                let raw = unsafe { *(ptr as *const *mut std::ffi::c_void) };
+                if raw.is_null() && self.kind != TypeKind::HString {
+                    return Ok(WinRTValue::Null);
+                }
+                self.from_out(raw)?
+            }
+            _ => self.from_out(ptr)?,
+        };
+        value.finish_out();
+        Ok(value)
+    }
+
     pub fn element_size(&self) -> usize {
         self.table.size_of_kind(self.kind)
     }
@@ -237,7 +373,8 @@ impl TypeHandle {
             TypeKind::I8 => WinRTValue::I8(0),
             TypeKind::U8 => WinRTValue::U8(0),
             TypeKind::I16 => WinRTValue::I16(0),
-            TypeKind::U16 | TypeKind::Char16 => WinRTValue::U16(0),
+            TypeKind::U16 => WinRTValue::U16(0),
+            TypeKind::Char16 => WinRTValue::Char16(0),
             TypeKind::I32 => WinRTValue::I32(0),
             TypeKind::Enum(_) => WinRTValue::Enum { value: 0, type_handle: self.clone() },
             TypeKind::U32 => WinRTValue::U32(0),
@@ -262,7 +399,9 @@ impl TypeHandle {
             TypeKind::Guid => WinRTValue::Guid(windows_core::GUID::zeroed()),
             TypeKind::HResult => WinRTValue::HResult(windows_core::HRESULT(0)),
 
-            TypeKind::OutValue(_) => WinRTValue::OutValue(std::ptr::null_mut(), self.clone()),
+            TypeKind::OutValue(_) => {
+                WinRTValue::OutValue(crate::value::OutSlot::new(std::ptr::null_mut(), self.out_value_inner()), self.clone())
+            }
 
             TypeKind::Generic { piid, .. } => {
                 panic!("Cannot create default value for Generic({:?})", piid)
@@ -277,9 +416,37 @@ impl TypeHandle {
             TypeKind::Array(_) => {
                 WinRTValue::Array(crate::array::ArrayData::empty(self.clone()))
             }
+
+            TypeKind::Unit => WinRTValue::Unit,
         }
     }
 
+    /// Buffer for a direct-call out param: hand `out_ptr()` to the callee, then
+    /// decode with `take_out`. Bool gets a `U8` slot so the callee's byte is
+    /// never reinterpreted in place as a Rust `bool`.
+    pub(crate) fn out_buffer(&self) -> WinRTValue {
+        match self.kind {
+            TypeKind::Bool => WinRTValue::U8(0),
+            _ => self.default_winrt_value(),
+        }
+    }
+
+    /// Decode an `out_buffer` after the callee has written it.
+    pub(crate) fn take_out(&self, out: WinRTValue) -> crate::result::Result<WinRTValue> {
+        let mut out = match (self.kind, out) {
+            (TypeKind::Bool, WinRTValue::U8(byte)) => WinRTValue::Bool(byte != 0),
+            // COM pointer types use RawPtr(null) as buffer; wrap what the callee wrote.
+            (_, WinRTValue::RawPtr(raw_ptr)) => self.from_out(raw_ptr)?,
+            (_, out) => out,
+        };
+        out.finish_out();
+        Ok(out)
+    }
+
+    /// Wrap what a callee wrote to an out param. Scalars are read through `ptr`;
+    /// for HSTRING and COM kinds `ptr` is the value itself and is taken over
+    /// as-is, since an out param hands the caller an owned reference (+1).
+    /// Use `from_out_borrowed` for pointers the caller does not own.
     pub fn from_out(&self, ptr: *mut std::ffi::c_void) -> crate::result::Result<WinRTValue> {
         unsafe {
             match self.kind {
@@ -287,9 +454,10 @@ impl TypeHandle {
                 TypeKind::I8 => Ok(WinRTValue::I8(*(ptr as *mut i8))),
                 TypeKind::U8 => Ok(WinRTValue::U8(*(ptr as *mut u8))),
                 TypeKind::I16 => Ok(WinRTValue::I16(*(ptr as *mut i16))),
-                TypeKind::U16 | TypeKind::Char16 => Ok(WinRTValue::U16(*(ptr as *mut u16))),
+                TypeKind::U16 => Ok(WinRTValue::U16(*(ptr as *mut u16))),
+                TypeKind::Char16 => Ok(WinRTValue::Char16(*(ptr as *mut u16))),
                 TypeKind::I32 => Ok(WinRTValue::I32(*(ptr as *mut i32))),
-                TypeKind::Enum(_) => Ok(WinRTValue::Enum { value: *(ptr as *mut i32), type_handle: self.clone() }),
+                TypeKind::Enum(_) => Ok(self.read_enum(ptr as *const u8)),
                 TypeKind::U32 => Ok(WinRTValue::U32(*(ptr as *mut u32))),
                 TypeKind::I64 => Ok(WinRTValue::I64(*(ptr as *mut i64))),
                 TypeKind::U64 => Ok(WinRTValue::U64(*(ptr as *mut u64))),
@@ -297,11 +465,9 @@ impl TypeHandle {
                 TypeKind::F64 => Ok(WinRTValue::F64(*(ptr as *mut f64))),
 
                 TypeKind::Object | TypeKind::Interface(_) | TypeKind::Delegate(_)
-                | TypeKind::RuntimeClass(_) => {
-                    Ok(WinRTValue::Object(IUnknown::from_raw(ptr)))
-                }
+                | TypeKind::RuntimeClass(_) => self.object_out(IUnknown::from_raw(ptr)),
 
-                TypeKind::HString => Ok(WinRTValue::HString(std::mem::transmute(ptr))),
+                TypeKind::HString => Ok(WinRTValue::HString(crate::value::hstring_from_abi(ptr))),
 
                 TypeKind::HResult => Ok(WinRTValue::HResult(windows_core::HRESULT(
                     *(ptr as *mut i32),
@@ -327,10 +493,7 @@ impl TypeHandle {
                     let raw = IUnknown::from_raw(ptr);
                     let info: windows_future::IAsyncInfo = raw.cast()
                         .map_err(|e| crate::result::Error::WindowsError(e))?;
-                    Ok(WinRTValue::Async(crate::value::AsyncInfo {
-                        info,
-                        async_type: self.clone(),
-                    }))
+                    Ok(WinRTValue::Async(crate::value::AsyncInfo::new(info, self.clone())))
                 }
 
                 _ => Err(crate::result::Error::InvalidTypeAbiToWinRT(
@@ -341,6 +504,47 @@ impl TypeHandle {
         }
     }
 
+    /// Wrap an owned object out value, QI'ing interface-typed outs to their IID
+    /// when the table asks for it.
+    fn object_out(&self, obj: IUnknown) -> crate::result::Result<WinRTValue> {
+        match self.kind {
+            TypeKind::Interface(iid) if !obj.as_raw().is_null() && self.table.requery_interface_outs() => {
+                let mut ptr = std::ptr::null_mut();
+                unsafe { obj.query(&iid, &mut ptr) }.ok()?;
+                Ok(WinRTValue::Object(unsafe { IUnknown::from_raw(ptr) }))
+            }
+            _ => Ok(WinRTValue::Object(obj)),
+        }
+    }
+
+    /// Like `from_out`, for a pointer that is only lent to us (a callback
+    /// argument, a field of a struct someone else frees). COM references are
+    /// AddRef'd and HSTRINGs duplicated, so the result owns its own reference.
+    pub fn from_out_borrowed(&self, ptr: *mut std::ffi::c_void) -> crate::result::Result<WinRTValue> {
+        let owned = match self.kind {
+            _ if ptr.is_null() => ptr,
+            TypeKind::HString => unsafe {
+                let hstr = &*(&ptr as *const *mut std::ffi::c_void as *const windows_core::HSTRING);
+                std::mem::transmute::<windows_core::HSTRING, *mut std::ffi::c_void>(hstr.clone())
+            },
+            TypeKind::IAsyncAction
+            | TypeKind::IAsyncActionWithProgress(_)
+            | TypeKind::IAsyncOperation(_)
+            | TypeKind::IAsyncOperationWithProgress(_) => {
+                unsafe { IUnknown::from_raw_borrowed(&ptr) }.unwrap().clone().into_raw()
+            }
+            kind if kind.is_com_pointer() => {
+                unsafe { IUnknown::from_raw_borrowed(&ptr) }.unwrap().clone().into_raw()
+            }
+            // Scalars are read through `ptr`; nothing is owned
+            _ => ptr,
+        };
+        let mut value = self.from_out(owned)?;
+        value.finish_out();
+        Ok(value)
+    }
+
+    /// Same ownership contract as `from_out`: pointer results are owned (+1).
     pub fn from_out_value(&self, out: &AbiValue) -> crate::result::Result<WinRTValue> {
         use crate::result::Error;
         match (self.kind, out) {
@@ -348,9 +552,17 @@ impl TypeHandle {
             (TypeKind::I8, AbiValue::I8(v)) => Ok(WinRTValue::I8(*v)),
             (TypeKind::U8, AbiValue::U8(v)) => Ok(WinRTValue::U8(*v)),
             (TypeKind::I16, AbiValue::I16(v)) => Ok(WinRTValue::I16(*v)),
-            (TypeKind::U16 | TypeKind::Char16, AbiValue::U16(v)) => Ok(WinRTValue::U16(*v)),
+            (TypeKind::U16, AbiValue::U16(v)) => Ok(WinRTValue::U16(*v)),
+            (TypeKind::Char16, AbiValue::U16(v)) => Ok(WinRTValue::Char16(*v)),
             (TypeKind::I32, AbiValue::I32(v)) => Ok(WinRTValue::I32(*v)),
-            (TypeKind::Enum(_), AbiValue::I32(v)) => Ok(WinRTValue::Enum { value: *v, type_handle: self.clone() }),
+            (TypeKind::Enum(_), AbiValue::I8(v)) => Ok(self.enum_from_bits(*v as i64)),
+            (TypeKind::Enum(_), AbiValue::U8(v)) => Ok(self.enum_from_bits(*v as i64)),
+            (TypeKind::Enum(_), AbiValue::I16(v)) => Ok(self.enum_from_bits(*v as i64)),
+            (TypeKind::Enum(_), AbiValue::U16(v)) => Ok(self.enum_from_bits(*v as i64)),
+            (TypeKind::Enum(_), AbiValue::I32(v)) => Ok(self.enum_from_bits(*v as i64)),
+            (TypeKind::Enum(_), AbiValue::U32(v)) => Ok(self.enum_from_bits(*v as i64)),
+            (TypeKind::Enum(_), AbiValue::I64(v)) => Ok(self.enum_from_bits(*v)),
+            (TypeKind::Enum(_), AbiValue::U64(v)) => Ok(self.enum_from_bits(*v as i64)),
             (TypeKind::U32, AbiValue::U32(v)) => Ok(WinRTValue::U32(*v)),
             (TypeKind::I64, AbiValue::I64(v)) => Ok(WinRTValue::I64(*v)),
             (TypeKind::U64, AbiValue::U64(v)) => Ok(WinRTValue::U64(*v)),
@@ -359,11 +571,11 @@ impl TypeHandle {
 
             (TypeKind::Object | TypeKind::Interface(_) | TypeKind::Delegate(_)
             | TypeKind::RuntimeClass(_), AbiValue::Pointer(p)) => {
-                Ok(WinRTValue::Object(unsafe { IUnknown::from_raw(*p) }))
+                self.object_out(unsafe { IUnknown::from_raw(*p) })
             }
 
             (TypeKind::HString, AbiValue::Pointer(p)) => {
-                Ok(WinRTValue::HString(unsafe { core::mem::transmute(*p) }))
+                Ok(WinRTValue::HString(unsafe { crate::value::hstring_from_abi(*p) }))
             }
 
             (TypeKind::HResult, AbiValue::I32(hr)) => {
@@ -415,6 +627,26 @@ fn is_async_piid(generic_def: TypeKind) -> bool {
         || piid == IASYNC_OPERATION_WITH_PROGRESS
 }
 
+/// Generic delegates whose parameterized instances `delegate_iid` accepts.
+fn is_delegate_piid(generic_def: TypeKind) -> bool {
+    let piid = match generic_def {
+        TypeKind::Generic { piid, .. } | TypeKind::Delegate(piid) => piid,
+        _ => return false,
+    };
+    [
+        TYPED_EVENT_HANDLER,
+        EVENT_HANDLER,
+        VECTOR_CHANGED_EVENT_HANDLER,
+        ASYNC_OPERATION_COMPLETED_HANDLER,
+        ASYNC_ACTION_WITH_PROGRESS_COMPLETED_HANDLER,
+        ASYNC_OPERATION_WITH_PROGRESS_COMPLETED_HANDLER,
+        ASYNC_ACTION_PROGRESS_HANDLER,
+        ASYNC_OPERATION_PROGRESS_HANDLER,
+    ]
+    .contains(&piid)
+}
+
+/// Takes ownership of `raw`; the returned `Async` value holds the only reference.
 fn make_async_value_from_kind(
     raw: IUnknown,
     generic_def: TypeKind,
@@ -455,5 +687,5 @@ fn make_async_value_from_kind(
         ));
     };
 
-    Ok(WinRTValue::Async(crate::value::AsyncInfo { info, async_type }))
+    Ok(WinRTValue::Async(crate::value::AsyncInfo::new(info, async_type)))
 }
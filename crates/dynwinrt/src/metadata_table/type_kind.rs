@@ -27,6 +27,12 @@ pub const IKEY_VALUE_PAIR: GUID =
     GUID::from_u128(0x02b51929_c1c4_4a7e_8940_0312b5c18500);
 pub const IOBSERVABLE_VECTOR: GUID =
     GUID::from_u128(0x5917eb53_50b4_4a0d_b309_65862b3f1dbc);
+pub const TYPED_EVENT_HANDLER: GUID =
+    GUID::from_u128(0x9de1c534_6ae1_11e0_84e1_18a905bcc53f);
+pub const EVENT_HANDLER: GUID =
+    GUID::from_u128(0x9de1c535_6ae1_11e0_84e1_18a905bcc53f);
+pub const VECTOR_CHANGED_EVENT_HANDLER: GUID =
+    GUID::from_u128(0x0c051752_9fbf_4c70_aa0c_0e4c82d9a761);
 pub const IREFERENCE: GUID =
     GUID::from_u128(0x61c17706_2d65_11e0_9ae8_d48564015472);
 
@@ -74,6 +80,8 @@ pub enum TypeKind {
     Object,
     HResult,
     ArrayOfIUnknown,
+    /// The value of a void result; never a parameter type.
+    Unit,
 
     // Types with inline GUID
     Interface(GUID),
@@ -108,7 +116,7 @@ impl TypeKind {
         match self {
             TypeKind::Bool | TypeKind::I8 | TypeKind::U8 => Some(1),
             TypeKind::I16 | TypeKind::U16 | TypeKind::Char16 => Some(2),
-            TypeKind::I32 | TypeKind::U32 | TypeKind::F32 | TypeKind::HResult | TypeKind::Enum(_) => Some(4),
+            TypeKind::I32 | TypeKind::U32 | TypeKind::F32 | TypeKind::HResult => Some(4),
             TypeKind::I64 | TypeKind::U64 | TypeKind::F64 => Some(8),
             TypeKind::Guid => Some(16),
             _ => None,
@@ -123,6 +131,7 @@ impl TypeKind {
     }
 
     /// libffi type for simple (non-struct) kinds. Returns `None` for Struct.
+    #[cfg(feature = "dynamic-call")]
     pub fn primitive_libffi_type(self) -> Option<libffi::middle::Type> {
         use libffi::middle::Type;
         match self {
@@ -141,6 +141,40 @@ impl ValueTypeData {
         unsafe { (self.ptr.add(offset) as *mut T).write(value) }
     }
 
+    /// Read a leaf field through nested structs: `&[0, 1]` is field 1 of the
+    /// struct in field 0.
+    pub fn get_field_path<T: Copy>(&self, path: &[usize]) -> T {
+        let (offset, field_handle) = self.field_path(path);
+        assert_eq!(
+            std::mem::size_of::<T>(),
+            field_handle.size_of(),
+            "get_field_path<T> size mismatch"
+        );
+        unsafe { (self.ptr.add(offset) as *const T).read() }
+    }
+
+    pub fn set_field_path<T: Copy>(&mut self, path: &[usize], value: T) {
+        let (offset, field_handle) = self.field_path(path);
+        assert_eq!(
+            std::mem::size_of::<T>(),
+            field_handle.size_of(),
+            "set_field_path<T> size mismatch"
+        );
+        unsafe { (self.ptr.add(offset) as *mut T).write(value) }
+    }
+
+    /// Byte offset from the start of this struct and type of the field at `path`.
+    fn field_path(&self, path: &[usize]) -> (usize, TypeHandle) {
+        assert!(!path.is_empty(), "field path must not be empty");
+        let mut offset = 0;
+        let mut handle = self.type_handle.clone();
+        for &index in path {
+            offset += handle.field_offset(index);
+            handle = handle.field_type(index);
+        }
+        (offset, handle)
+    }
+
     pub fn get_field_struct(&self, index: usize) -> ValueTypeData {
         let h = &self.type_handle;
         let offset = h.field_offset(index);
@@ -191,36 +225,6 @@ impl ValueTypeData {
             }
         }
     }
-
-    pub fn call_method_struct_to_object(
-        &self,
-        obj_raw: *mut std::ffi::c_void,
-        method_index: usize,
-    ) -> windows_core::Result<windows_core::IUnknown> {
-        use crate::call::get_vtable_function_ptr;
-        use libffi::middle::{arg, Cif, CodePtr, Type};
-
-        let fptr = get_vtable_function_ptr(obj_raw, method_index);
-        let cif = Cif::new(
-            vec![
-                Type::pointer(),
-                self.type_handle.libffi_type(),
-                Type::pointer(),
-            ],
-            Type::i32(),
-        );
-
-        let mut out: *mut std::ffi::c_void = std::ptr::null_mut();
-        let data_ref = unsafe { &*self.ptr };
-        let hr: windows_core::HRESULT = unsafe {
-            cif.call(
-                CodePtr(fptr),
-                &[arg(&obj_raw), arg(data_ref), arg(&(&mut out))],
-            )
-        };
-        hr.ok()?;
-        Ok(unsafe { windows_core::IUnknown::from_raw(out as _) })
-    }
 }
 
 impl Drop for ValueTypeData {
@@ -0,0 +1,472 @@
+//! Synthetic COM objects for testing dynamic dispatch without real WinRT
+//! classes: a vtable of `extern "system"` functions with known behavior.
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use windows_core::{GUID, HRESULT, IUnknown, Interface};
+
+use crate::value::WinRTValue;
+
+/// A COM object whose vtable is IUnknown, IInspectable (no iids, no class
+/// name, base trust), then `methods` from slot 6 on. Every QI succeeds with
+/// the same object, except `IAgileObject` on mocks built with
+/// `new_non_agile`, and anything beyond the base interfaces on mocks built
+/// with `with_interfaces`.
+/// The mock owns its storage: Release only counts, so tests can check the
+/// count reaches zero while the object is still alive.
+#[repr(C)]
+pub(crate) struct MockObject {
+    // Only read by callers, through the object pointer
+    #[allow(dead_code)]
+    vtable: *const *const c_void,
+    refs: AtomicU32,
+    slots: Box<[*const c_void]>,
+    agile: bool,
+    /// QI allow-list beyond IUnknown, IInspectable and IAgileObject; `None` answers everything.
+    interfaces: Option<Box<[GUID]>>,
+}
+
+impl MockObject {
+    pub(crate) fn new(methods: &[*const c_void]) -> Box<Self> {
+        Self::build(methods, true, None)
+    }
+
+    /// A mock that fails the QI for `IAgileObject`.
+    pub(crate) fn new_non_agile(methods: &[*const c_void]) -> Box<Self> {
+        Self::build(methods, false, None)
+    }
+
+    /// A mock that answers QI only for the base interfaces and `interfaces`,
+    /// for callers that probe optional interfaces (e.g. IPropertyValue).
+    pub(crate) fn with_interfaces(interfaces: &[GUID], methods: &[*const c_void]) -> Box<Self> {
+        Self::build(methods, true, Some(interfaces.into()))
+    }
+
+    fn build(methods: &[*const c_void], agile: bool, interfaces: Option<Box<[GUID]>>) -> Box<Self> {
+        let mut slots = vec![
+            query_interface as *const c_void,
+            add_ref as *const c_void,
+            release as *const c_void,
+            get_iids as *const c_void,
+            get_runtime_class_name as *const c_void,
+            get_trust_level as *const c_void,
+        ];
+        slots.extend_from_slice(methods);
+        let slots = slots.into_boxed_slice();
+        Box::new(MockObject { vtable: slots.as_ptr(), refs: AtomicU32::new(0), slots, agile, interfaces })
+    }
+
+    pub(crate) fn as_raw(&self) -> *mut c_void {
+        self as *const Self as *mut c_void
+    }
+
+    /// A new reference to the mock as a `WinRTValue::Object`.
+    pub(crate) fn value(&self) -> WinRTValue {
+        unsafe { add_ref(self.as_raw()) };
+        WinRTValue::Object(unsafe { IUnknown::from_raw(self.as_raw()) })
+    }
+
+    pub(crate) fn refs(&self) -> u32 {
+        self.refs.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+unsafe extern "system" fn query_interface(this: *mut c_void, iid: *const GUID, out: *mut *mut c_void) -> HRESULT {
+    unsafe {
+        let mock = &*(this as *const MockObject);
+        let iid = *iid;
+        let answers = if iid == windows_core::imp::IAgileObject::IID {
+            mock.agile
+        } else {
+            iid == IUnknown::IID
+                || iid == windows_core::IInspectable::IID
+                || mock.interfaces.as_ref().is_none_or(|list| list.contains(&iid))
+        };
+        if !answers {
+            *out = std::ptr::null_mut();
+            return HRESULT(0x80004002u32 as i32); // E_NOINTERFACE
+        }
+        add_ref(this);
+        *out = this;
+    }
+    HRESULT(0)
+}
+
+unsafe extern "system" fn add_ref(this: *mut c_void) -> u32 {
+    unsafe { (*(this as *const MockObject)).refs.fetch_add(1, Ordering::SeqCst) + 1 }
+}
+
+unsafe extern "system" fn release(this: *mut c_void) -> u32 {
+    unsafe { (*(this as *const MockObject)).refs.fetch_sub(1, Ordering::SeqCst) - 1 }
+}
+
+unsafe extern "system" fn get_iids(_: *mut c_void, count: *mut u32, iids: *mut *mut GUID) -> HRESULT {
+    unsafe {
+        *count = 0;
+        *iids = std::ptr::null_mut();
+    }
+    HRESULT(0)
+}
+
+unsafe extern "system" fn get_runtime_class_name(_: *mut c_void, name: *mut *mut c_void) -> HRESULT {
+    unsafe { *name = std::ptr::null_mut() };
+    HRESULT(0)
+}
+
+unsafe extern "system" fn get_trust_level(_: *mut c_void, level: *mut i32) -> HRESULT {
+    unsafe { *level = 0 };
+    HRESULT(0)
+}
+
+#[cfg(test)]
+// Most fixtures here serve the libffi tests and go unused without dynamic-call
+#[cfg_attr(not(feature = "dynamic-call"), allow(dead_code, unused_imports))]
+mod tests {
+    use std::sync::atomic::{AtomicI32, AtomicU16};
+
+    use windows_core::HSTRING;
+
+    use super::*;
+    use crate::metadata_table::MetadataTable;
+    use crate::signature::{MethodSignature, ParamSpec};
+
+    static STORED: AtomicI32 = AtomicI32::new(0);
+
+    // 6: Add(i32, i32, out i32)
+    unsafe extern "system" fn add(_: *mut c_void, a: i32, b: i32, out: *mut i32) -> HRESULT {
+        unsafe { *out = a + b };
+        HRESULT(0)
+    }
+    // 7: get_Answer(out i32)
+    unsafe extern "system" fn get_answer(_: *mut c_void, out: *mut i32) -> HRESULT {
+        unsafe { *out = 42 };
+        HRESULT(0)
+    }
+    // 8: put_Stored(i32)
+    unsafe extern "system" fn put_stored(_: *mut c_void, value: i32) -> HRESULT {
+        STORED.store(value, Ordering::SeqCst);
+        HRESULT(0)
+    }
+    // 9: Greet(HSTRING, out HSTRING)
+    unsafe extern "system" fn greet(_: *mut c_void, name: *mut c_void, out: *mut *mut c_void) -> HRESULT {
+        let name = unsafe { &*(&name as *const *mut c_void as *const HSTRING) };
+        let greeting = HSTRING::from(format!("hello, {name}"));
+        unsafe { *out = std::mem::transmute::<HSTRING, *mut c_void>(greeting) };
+        HRESULT(0)
+    }
+    // 10: Fail() -> E_FAIL
+    unsafe extern "system" fn fail(_: *mut c_void) -> HRESULT {
+        HRESULT(0x80004005u32 as i32)
+    }
+
+    #[repr(C)]
+    struct Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    }
+    // 6: get_Bounds(out Rect): a 16-byte struct return, via trailing out pointer
+    unsafe extern "system" fn get_bounds(_: *mut c_void, out: *mut Rect) -> HRESULT {
+        unsafe { *out = Rect { x: 1.0, y: 2.0, width: 640.0, height: 480.0 } };
+        HRESULT(0)
+    }
+
+    fn calculator() -> Box<MockObject> {
+        MockObject::new(&[
+            add as *const c_void,
+            get_answer as *const c_void,
+            put_stored as *const c_void,
+            greet as *const c_void,
+            fail as *const c_void,
+        ])
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn call_dynamic_dispatches_to_mock_slots() {
+        let mock = calculator();
+        assert_eq!(mock.slot_count(), 11);
+        let table = MetadataTable::new();
+        let i32_t = table.i32_type();
+
+        let add = MethodSignature::new(&table)
+            .add_in(i32_t.clone())
+            .add_in(i32_t.clone())
+            .add_out(i32_t.clone())
+            .build(6);
+        let outs = add.call_dynamic(mock.as_raw(), &[WinRTValue::I32(40), WinRTValue::I32(2)]).unwrap();
+        assert_eq!(outs, vec![WinRTValue::I32(42)]);
+
+        let get_answer = MethodSignature::new(&table).add_out(i32_t.clone()).build(7);
+        assert_eq!(get_answer.call_dynamic(mock.as_raw(), &[]).unwrap(), vec![WinRTValue::I32(42)]);
+
+        let put_stored = MethodSignature::new(&table).add_in(i32_t).build(8);
+        assert!(put_stored.call_dynamic(mock.as_raw(), &[WinRTValue::I32(-7)]).unwrap().is_empty());
+        assert_eq!(STORED.load(Ordering::SeqCst), -7);
+
+        let greet = MethodSignature::new(&table).add_in(table.hstring()).add_out(table.hstring()).build(9);
+        assert_eq!(
+            greet.call_dynamic(mock.as_raw(), &[WinRTValue::string("mock")]).unwrap(),
+            vec![WinRTValue::string("hello, mock")]
+        );
+
+        let fail = MethodSignature::new(&table).build(10);
+        let err = fail.call_dynamic(mock.as_raw(), &[]).unwrap_err();
+        assert_eq!(err.code(), HRESULT(0x80004005u32 as i32));
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn struct_return_larger_than_8_bytes_reads_back() {
+        let mock = MockObject::new(&[get_bounds as *const c_void]);
+        let table = MetadataTable::new();
+        let f32_t = table.f32_type();
+        let rect = table.struct_type("Windows.Foundation.Rect", &[f32_t.clone(), f32_t.clone(), f32_t.clone(), f32_t]);
+        assert_eq!(rect.size_of(), 16);
+
+        let get_bounds = MethodSignature::new(&table).add_out(rect).build(6);
+        let bounds = match get_bounds.call_dynamic(mock.as_raw(), &[]).unwrap().remove(0) {
+            WinRTValue::Struct(data) => data,
+            other => panic!("expected Struct, got {other:?}"),
+        };
+        let fields: Vec<f32> = (0..4).map(|i| bounds.get_field(i)).collect();
+        assert_eq!(fields, [1.0, 2.0, 640.0, 480.0]);
+    }
+
+    // 6: get_Char(out Char16) with a code unit set by the test; 7: Next(Char16, out Char16)
+    static UNIT: AtomicU16 = AtomicU16::new(0);
+    unsafe extern "system" fn get_char(_: *mut c_void, out: *mut u16) -> HRESULT {
+        unsafe { *out = UNIT.load(Ordering::SeqCst) };
+        HRESULT(0)
+    }
+    unsafe extern "system" fn next_char(_: *mut c_void, c: u16, out: *mut u16) -> HRESULT {
+        unsafe { *out = c + 1 };
+        HRESULT(0)
+    }
+
+    #[test]
+    fn char16_outs_decode_as_chars() {
+        let mock = MockObject::new(&[get_char as *const c_void, next_char as *const c_void]);
+        let table = MetadataTable::new();
+        let char16 = table.char16_type();
+        let get_char = MethodSignature::new(&table).add_out(char16.clone()).build(6);
+
+        UNIT.store('é' as u16, Ordering::SeqCst);
+        let out = get_char.call_dynamic(mock.as_raw(), &[]).unwrap().remove(0);
+        assert_eq!(out, WinRTValue::Char16('é' as u16));
+        assert_ne!(out, WinRTValue::U16('é' as u16));
+        assert_eq!(out.as_char(), Some('é'));
+
+        // A lone high surrogate is no character: replaced, not an error
+        UNIT.store(0xD83D, Ordering::SeqCst);
+        let out = get_char.call_dynamic(mock.as_raw(), &[]).unwrap().remove(0);
+        assert_eq!(out.as_char(), Some(char::REPLACEMENT_CHARACTER));
+
+        let next = MethodSignature::new(&table).add_in(char16.clone()).add_out(char16).build(7);
+        let out = next.call_dynamic(mock.as_raw(), &[WinRTValue::Char16('a' as u16)]).unwrap().remove(0);
+        assert_eq!(out.as_char(), Some('b'));
+        assert_eq!(WinRTValue::U16(98).as_char(), None);
+    }
+
+    // 6: Configure(i32, IOptions options, out i32): -1 when options is absent
+    // 7: put_Options(IOptions options)
+    static HAS_OPTIONS: AtomicI32 = AtomicI32::new(-1);
+    unsafe extern "system" fn configure(_: *mut c_void, level: i32, options: *mut c_void, out: *mut i32) -> HRESULT {
+        unsafe { *out = if options.is_null() { -1 } else { level } };
+        HRESULT(0)
+    }
+    unsafe extern "system" fn put_options(_: *mut c_void, options: *mut c_void) -> HRESULT {
+        HAS_OPTIONS.store(!options.is_null() as i32, Ordering::SeqCst);
+        HRESULT(0)
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn null_passes_absent_optional_interface() {
+        let mock = MockObject::new(&[configure as *const c_void, put_options as *const c_void]);
+        let options = calculator();
+        let table = MetadataTable::new();
+        let ioptions = table.interface(GUID::from_u128(0x1234));
+
+        // Two ins: dispatched through libffi
+        let configure = MethodSignature::new(&table)
+            .add_in(table.i32_type())
+            .add_in(ioptions.clone())
+            .add_out(table.i32_type())
+            .build(6);
+        let absent = configure.call_dynamic(mock.as_raw(), &[WinRTValue::I32(3), WinRTValue::Null]).unwrap();
+        assert_eq!(absent, vec![WinRTValue::I32(-1)]);
+        let present = configure.call_dynamic(mock.as_raw(), &[WinRTValue::I32(3), options.value()]).unwrap();
+        assert_eq!(present, vec![WinRTValue::I32(3)]);
+
+        // One in: the direct setter path
+        let put_options = MethodSignature::new(&table).add_in(ioptions).build(7);
+        put_options.call_dynamic(mock.as_raw(), &[WinRTValue::Null]).unwrap();
+        assert_eq!(HAS_OPTIONS.load(Ordering::SeqCst), 0);
+        assert_eq!(options.refs(), 0);
+    }
+
+    // 6: get_LastError(out HRESULT); 7: Check(i32, i32, out HRESULT)
+    unsafe extern "system" fn get_last_error(_: *mut c_void, out: *mut HRESULT) -> HRESULT {
+        unsafe { *out = HRESULT(0x80070005u32 as i32) }; // E_ACCESSDENIED
+        HRESULT(0)
+    }
+    unsafe extern "system" fn check(_: *mut c_void, a: i32, b: i32, out: *mut HRESULT) -> HRESULT {
+        unsafe { *out = if a == b { HRESULT(0) } else { HRESULT(0x80004005u32 as i32) } };
+        HRESULT(0)
+    }
+
+    #[test]
+    fn hresult_out_params_decode_without_failing_the_call() {
+        let mock = MockObject::new(&[get_last_error as *const c_void, check as *const c_void]);
+        let table = MetadataTable::new();
+
+        // The method's own HRESULT is S_OK; the out param carries a failure as data
+        let get_last_error = MethodSignature::new(&table).add_out(table.hresult()).build(6);
+        let out = get_last_error.call_dynamic(mock.as_raw(), &[]).unwrap().remove(0);
+        assert_eq!(out, WinRTValue::HResult(HRESULT(0x80070005u32 as i32)));
+        assert_eq!(out.err().unwrap().code(), HRESULT(0x80070005u32 as i32));
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn hresult_out_params_decode_through_libffi() {
+        let mock = MockObject::new(&[get_last_error as *const c_void, check as *const c_void]);
+        let table = MetadataTable::new();
+
+        // Two ins: the out HRESULT decodes the same as on the direct path
+        let i32_t = table.i32_type();
+        let check = MethodSignature::new(&table).add_in(i32_t.clone()).add_in(i32_t).add_out(table.hresult()).build(7);
+        let same = check.call_dynamic(mock.as_raw(), &[WinRTValue::I32(1), WinRTValue::I32(1)]).unwrap();
+        assert_eq!(same, vec![WinRTValue::HResult(HRESULT(0))]);
+        let differ = check.call_dynamic(mock.as_raw(), &[WinRTValue::I32(1), WinRTValue::I32(2)]).unwrap();
+        assert!(!differ[0].is_ok());
+    }
+
+    // 6: Join(HSTRING, HSTRING, out HSTRING)
+    unsafe extern "system" fn join(_: *mut c_void, a: *mut c_void, b: *mut c_void, out: *mut *mut c_void) -> HRESULT {
+        let (a, b) = unsafe {
+            (&*(&a as *const *mut c_void as *const HSTRING), &*(&b as *const *mut c_void as *const HSTRING))
+        };
+        let joined = HSTRING::from(format!("{a}/{b}"));
+        unsafe { *out = std::mem::transmute::<HSTRING, *mut c_void>(joined) };
+        HRESULT(0)
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn temporary_hstring_args_live_through_libffi_call() {
+        let mock = MockObject::new(&[join as *const c_void]);
+        let table = MetadataTable::new();
+        let join = MethodSignature::new(&table)
+            .add_in(table.hstring())
+            .add_in(table.hstring())
+            .add_out(table.hstring())
+            .build(6);
+
+        // Two ins: the libffi path, with both HSTRINGs built inline in the call expression
+        for i in 0..16 {
+            let outs = join
+                .call_dynamic(mock.as_raw(), &[
+                    WinRTValue::HString(HSTRING::from(format!("left-{i}"))),
+                    WinRTValue::HString(HSTRING::from("x".repeat(64 + i))),
+                ])
+                .unwrap();
+            assert_eq!(outs, vec![WinRTValue::HString(HSTRING::from(format!("left-{i}/{}", "x".repeat(64 + i))))]);
+        }
+    }
+
+    // 6: Scale(i32 factor, u32 length, [in, out] i32* data); 7: Bump([in, out] i32* value);
+    // 8: Toggle([in, out] bool* value), writing a non-canonical true
+    unsafe extern "system" fn toggle(_: *mut c_void, value: *mut u8) -> HRESULT {
+        unsafe { *value = if *value == 0 { 2 } else { 0 } };
+        HRESULT(0)
+    }
+    unsafe extern "system" fn scale(_: *mut c_void, factor: i32, len: u32, data: *mut i32) -> HRESULT {
+        for i in 0..len as usize {
+            unsafe { *data.add(i) *= factor };
+        }
+        HRESULT(0)
+    }
+    unsafe extern "system" fn bump(_: *mut c_void, value: *mut i32) -> HRESULT {
+        unsafe { *value += 1 };
+        HRESULT(0)
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn in_out_params_are_updated_in_place() {
+        let mock = MockObject::new(&[scale as *const c_void, bump as *const c_void, toggle as *const c_void]);
+        let table = MetadataTable::new();
+        let i32_t = table.i32_type();
+
+        let scale = MethodSignature::new(&table)
+            .add_in(i32_t.clone())
+            .add_in_out(table.array(&i32_t))
+            .build(6);
+        assert_eq!(scale.in_count(), 2);
+        let input = WinRTValue::i32_array(&table, &[1, 2, 3]);
+        let outs = scale.call_dynamic(mock.as_raw(), &[WinRTValue::I32(10), input.clone()]).unwrap();
+        assert_eq!(outs.len(), 1);
+        assert_eq!(outs[0].as_array().unwrap().to_values(), vec![
+            WinRTValue::I32(10), WinRTValue::I32(20), WinRTValue::I32(30),
+        ]);
+        // The caller's array is copied, not written through
+        assert_eq!(input.as_array().unwrap().get(0), WinRTValue::I32(1));
+
+        let bump = MethodSignature::new(&table).add_in_out(i32_t).build(7);
+        assert_eq!(bump.call_dynamic(mock.as_raw(), &[WinRTValue::I32(41)]).unwrap(), vec![WinRTValue::I32(42)]);
+
+        let toggle = MethodSignature::new(&table).add_in_out(table.bool_type()).build(8);
+        assert_eq!(toggle.call_dynamic(mock.as_raw(), &[WinRTValue::Bool(false)]).unwrap(), vec![WinRTValue::Bool(true)]);
+        assert_eq!(toggle.call_dynamic(mock.as_raw(), &[WinRTValue::Bool(true)]).unwrap(), vec![WinRTValue::Bool(false)]);
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn in_out_argument_errors_are_invalid_arg() {
+        const E_INVALIDARG: HRESULT = HRESULT(0x80070057u32 as i32);
+        let mock = MockObject::new(&[scale as *const c_void, bump as *const c_void]);
+        let table = MetadataTable::new();
+        let i32_t = table.i32_type();
+
+        // Wrong scalar kind, a non-array for an array, a non-blittable type
+        let bump = MethodSignature::new(&table).add_in_out(i32_t.clone()).build(7);
+        let err = bump.call_dynamic(mock.as_raw(), &[WinRTValue::I64(41)]).unwrap_err();
+        assert_eq!(err.code(), E_INVALIDARG);
+        let scale = MethodSignature::new(&table)
+            .add_in(i32_t.clone())
+            .add_in_out(table.array(&i32_t))
+            .build(6);
+        let err = scale.call_dynamic(mock.as_raw(), &[WinRTValue::I32(10), WinRTValue::I32(1)]).unwrap_err();
+        assert_eq!(err.code(), E_INVALIDARG);
+        let strings = MethodSignature::new(&table).add_in_out(table.hstring()).build(7);
+        let err = strings.call_dynamic(mock.as_raw(), &[WinRTValue::HString(Default::default())]).unwrap_err();
+        assert_eq!(err.code(), E_INVALIDARG);
+        assert_eq!(mock.refs(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn value_call_on_mock_balances_refcount() {
+        let mock = calculator();
+        let table = MetadataTable::new();
+        let i32_t = table.i32_type();
+        {
+            let value = mock.value();
+            let params = [ParamSpec::In(i32_t.clone()), ParamSpec::In(i32_t.clone()), ParamSpec::Out(i32_t)];
+            let outs = value.call(6, &params, &[WinRTValue::I32(1), WinRTValue::I32(2)]).unwrap();
+            assert_eq!(outs, vec![WinRTValue::I32(3)]);
+            let cast = value.cast(&GUID::from_u128(0x1234)).unwrap();
+            assert_eq!(mock.refs(), 2);
+            drop(cast);
+        }
+        assert_eq!(mock.refs(), 0);
+    }
+}
@@ -0,0 +1,43 @@
+//! The types most dynamic calls need, for a single glob import:
+//!
+//! ```ignore
+//! use dynwinrt::prelude::*;
+//! ```
+//!
+//! Only add items here that are part of the stable surface; removing one is a
+//! breaking change for every `use dynwinrt::prelude::*`.
+
+pub use crate::metadata_table::{MetadataTable, TypeHandle, TypeKind};
+pub use crate::result::{Error, Result};
+pub use crate::signature::{InterfaceSignature, MethodSignature, ParamSpec};
+pub use crate::value::WinRTValue;
+
+#[cfg(all(test, feature = "dynamic-call"))]
+mod tests {
+    use windows_core::{Interface, h};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn dynamic_call_through_prelude() -> Result<()> {
+        let _ = crate::ensure_mta();
+
+        // IUriRuntimeClassFactory::CreateUri, then IUriRuntimeClass::get_Path (index 13)
+        let table = MetadataTable::new();
+        let factory = WinRTValue::from_activation_factory(h!("Windows.Foundation.Uri"))?
+            .cast(&crate::IURI_RUNTIME_CLASS_FACTORY)?;
+        let uri = factory
+            .call(
+                6,
+                &[ParamSpec::In(table.hstring()), ParamSpec::Out(table.object())],
+                &[WinRTValue::HString(h!("https://www.example.com/a/b").clone())],
+            )?
+            .remove(0);
+        let obj = uri.as_object().ok_or_else(|| Error::expect_object_type(uri.get_type_kind()))?;
+
+        let get_path = MethodSignature::new(&table).add_out(table.hstring()).build(13);
+        let path = get_path.call_dynamic(obj.as_raw(), &[])?;
+        assert_eq!(path, vec![WinRTValue::HString(h!("/a/b").clone())]);
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use windows::Foundation::{IPropertyValue, PropertyType, PropertyValue};
+use windows::Win32::System::WinRT::IActivationFactory;
+use windows_core::{HSTRING, IInspectable, Interface, h};
+
+use crate::iterable::DynIterable;
+use crate::metadata_table::{MetadataTable, TypeKind};
+use crate::result::{Error, Result};
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
+
+/// A WinRT `PropertySet` (`IMap<String, Object>`) driven through dynamic calls.
+///
+/// Useful when an API expects an `IPropertySet` / `IMap<String, Object>` and the
+/// caller only has `WinRTValue`s. String values are boxed via `PropertyValue` on
+/// insert and unboxed on lookup; other values must already be objects.
+pub struct DynPropertySet {
+    map: WinRTValue,
+    table: Arc<MetadataTable>,
+}
+
+impl DynPropertySet {
+    /// Activate a new, empty `Windows.Foundation.Collections.PropertySet`.
+    pub fn new() -> Result<Self> {
+        let table = MetadataTable::new();
+        let factory = WinRTValue::from_activation_factory(h!("Windows.Foundation.Collections.PropertySet"))?
+            .cast(&IActivationFactory::IID)?;
+        // IActivationFactory::ActivateInstance at vtable index 6
+        let instance = factory
+            .call(6, &[ParamSpec::Out(table.object())], &[])?
+            .remove(0);
+        let iids = table.map_iids(&table.hstring(), &table.object());
+        let map = instance.cast(&iids.map)?;
+        Ok(DynPropertySet { map, table })
+    }
+
+    /// The underlying `IMap<String, Object>` object, for passing to other APIs.
+    pub fn as_value(&self) -> &WinRTValue {
+        &self.map
+    }
+
+    /// `IMap::Insert` (vtable index 10). Returns true if an existing entry was replaced.
+    pub fn insert(&self, key: &str, value: WinRTValue) -> Result<bool> {
+        let value = box_value(value)?;
+        let replaced = self.map.call(
+            10,
+            &[
+                ParamSpec::In(self.table.hstring()),
+                ParamSpec::In(self.table.object()),
+                ParamSpec::Out(self.table.bool_type()),
+            ],
+            &[WinRTValue::HString(HSTRING::from(key)), value],
+        )?;
+        Ok(matches!(replaced.first(), Some(WinRTValue::Bool(true))))
+    }
+
+    /// `IMap::Lookup` (vtable index 6). Boxed strings and scalars are unboxed.
+    pub fn get(&self, key: &str) -> Result<WinRTValue> {
+        let value = self.map
+            .call(
+                6,
+                &[ParamSpec::In(self.table.hstring()), ParamSpec::Out(self.table.object())],
+                &[WinRTValue::HString(HSTRING::from(key))],
+            )?
+            .remove(0);
+        unbox_value(value)
+    }
+
+    /// Every entry, by iterating the map as `IIterable<IKeyValuePair<String, Object>>`
+    /// and reading `get_Key` (6) / `get_Value` (7) of each pair. Values are
+    /// unboxed like `get`; order is whatever the map yields.
+    pub fn entries(&self) -> Result<Vec<(HSTRING, WinRTValue)>> {
+        let (hstring, object) = (self.table.hstring(), self.table.object());
+        let kvp_iid = self.table.map_iids(&hstring, &object).kvp;
+        let pairs = DynIterable::new(&self.map, &self.table.key_value_pair(&hstring, &object))?;
+        pairs
+            .iter()?
+            .map(|pair| {
+                let pair = pair?.cast(&kvp_iid)?;
+                let key = pair.call(6, &[ParamSpec::Out(hstring.clone())], &[])?.remove(0);
+                let value = pair.call(7, &[ParamSpec::Out(object.clone())], &[])?.remove(0);
+                let key = key.as_hstring().ok_or_else(|| Error::InvalidType(TypeKind::HString, key.get_type_kind()))?;
+                Ok((key, unbox_value(value)?))
+            })
+            .collect()
+    }
+}
+
+fn box_value(value: WinRTValue) -> Result<WinRTValue> {
+    match value {
+        WinRTValue::HString(s) => {
+            let boxed: IInspectable = PropertyValue::CreateString(&s)?;
+            Ok(WinRTValue::Object(boxed.cast()?))
+        }
+        WinRTValue::Object(_) | WinRTValue::Null => Ok(value),
+        other => Err(Error::ExpectObjectTypeError(other.get_type_kind())),
+    }
+}
+
+/// Unbox `PropertyValue` strings and scalars (`IReference<T>`); other values,
+/// including boxed arrays and non-`IPropertyValue` objects, pass through.
+fn unbox_value(value: WinRTValue) -> Result<WinRTValue> {
+    let Some(obj) = value.as_object() else {
+        return Ok(value);
+    };
+    let Ok(pv) = obj.cast::<IPropertyValue>() else {
+        return Ok(value);
+    };
+    Ok(match pv.Type()? {
+        PropertyType::String => WinRTValue::HString(pv.GetString()?),
+        PropertyType::Boolean => WinRTValue::Bool(pv.GetBoolean()?),
+        PropertyType::UInt8 => WinRTValue::U8(pv.GetUInt8()?),
+        PropertyType::Int16 => WinRTValue::I16(pv.GetInt16()?),
+        PropertyType::UInt16 => WinRTValue::U16(pv.GetUInt16()?),
+        PropertyType::Char16 => WinRTValue::Char16(pv.GetChar16()?),
+        PropertyType::Int32 => WinRTValue::I32(pv.GetInt32()?),
+        PropertyType::UInt32 => WinRTValue::U32(pv.GetUInt32()?),
+        PropertyType::Int64 => WinRTValue::I64(pv.GetInt64()?),
+        PropertyType::UInt64 => WinRTValue::U64(pv.GetUInt64()?),
+        PropertyType::Single => WinRTValue::F32(pv.GetSingle()?),
+        PropertyType::Double => WinRTValue::F64(pv.GetDouble()?),
+        PropertyType::Guid => WinRTValue::Guid(pv.GetGuid()?),
+        _ => value,
+    })
+}
+
+#[cfg(all(test, feature = "dynamic-call"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get_string() {
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let set = DynPropertySet::new().unwrap();
+        assert!(!set.insert("name", WinRTValue::HString(h!("dynwinrt").clone())).unwrap());
+        assert!(set.insert("name", WinRTValue::HString(h!("dynwinrt2").clone())).unwrap());
+        assert_eq!(set.get("name").unwrap(), WinRTValue::HString(h!("dynwinrt2").clone()));
+
+        // Visible through the static projection too
+        let map: windows_collections::IMap<HSTRING, IInspectable> =
+            set.as_value().as_object().unwrap().cast().unwrap();
+        assert_eq!(map.Size().unwrap(), 1);
+
+        // Missing keys surface Lookup's E_BOUNDS
+        assert!(set.get("missing").is_err());
+    }
+
+    #[test]
+    fn entries_unbox_values() {
+        use windows::Foundation::Uri;
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let set = DynPropertySet::new().unwrap();
+        assert!(set.entries().unwrap().is_empty());
+
+        let count: IInspectable = PropertyValue::CreateInt32(3).unwrap();
+        let letter: IInspectable = PropertyValue::CreateChar16(u16::from(b'x')).unwrap();
+        let uri = Uri::CreateUri(h!("https://www.example.com")).unwrap();
+        set.insert("name", WinRTValue::HString(h!("dynwinrt").clone())).unwrap();
+        set.insert("count", WinRTValue::Object(count.cast().unwrap())).unwrap();
+        set.insert("letter", WinRTValue::Object(letter.cast().unwrap())).unwrap();
+        set.insert("uri", WinRTValue::Object(uri.cast().unwrap())).unwrap();
+
+        let mut entries = set.entries().unwrap();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let keys: Vec<_> = entries.iter().map(|(k, _)| k.to_string()).collect();
+        assert_eq!(keys, ["count", "letter", "name", "uri"]);
+        assert_eq!(entries[0].1, WinRTValue::I32(3));
+        // Char16 stays distinct from U16
+        assert_eq!(entries[1].1, WinRTValue::Char16(u16::from(b'x')));
+        assert_eq!(entries[2].1, WinRTValue::HString(h!("dynwinrt").clone()));
+        // Not an IPropertyValue: left as the object
+        assert_eq!(entries[3].1, WinRTValue::Object(uri.cast().unwrap()));
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use windows_core::GUID;
+
+use crate::metadata_table::{MetadataTable, TypeKind};
+use crate::result::{Error, Result};
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
+
+/// Microsoft.Windows.AI.Imaging.IRecognizedText
+pub const IRECOGNIZED_TEXT: GUID = GUID::from_u128(0xae4766d3_2924_57a6_b3d3_b866f59b9972);
+
+/// `IRecognizedText::get_Lines`: ReceiveArray of `RecognizedLine`.
+const LINES_SLOT: usize = 6;
+/// `IRecognizedLine::get_Text`, called on each line's default interface.
+const LINE_TEXT_SLOT: usize = 7;
+
+/// OCR output (`Microsoft.Windows.AI.Imaging.RecognizedText`) read through
+/// dynamic calls, without the generated projection.
+pub struct DynRecognizedText {
+    text: WinRTValue,
+    table: Arc<MetadataTable>,
+}
+
+impl DynRecognizedText {
+    /// QI `value` for IRecognizedText, e.g. the result of
+    /// `TextRecognizer::RecognizeTextFromImageAsync`.
+    pub fn new(value: &WinRTValue) -> Result<Self> {
+        Ok(DynRecognizedText { text: value.cast(&IRECOGNIZED_TEXT)?, table: MetadataTable::new() })
+    }
+
+    /// The underlying IRecognizedText object, for passing to other APIs.
+    pub fn as_value(&self) -> &WinRTValue {
+        &self.text
+    }
+
+    /// The `Text` of every entry in `Lines`, in recognition order.
+    pub fn lines(&self) -> Result<Vec<String>> {
+        let lines = self.text
+            .call(LINES_SLOT, &[ParamSpec::Out(self.table.array(&self.table.object()))], &[])?
+            .remove(0);
+        let lines = lines.as_array()
+            .ok_or_else(|| Error::InvalidType(TypeKind::Array, lines.get_type_kind()))?;
+        lines
+            .to_values()
+            .iter()
+            .map(|line| {
+                let text = line
+                    .call(LINE_TEXT_SLOT, &[ParamSpec::Out(self.table.hstring())], &[])?
+                    .remove(0);
+                text.as_hstring()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| Error::InvalidType(TypeKind::HString, text.get_type_kind()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+
+    use windows::Win32::System::Com::CoTaskMemAlloc;
+    use windows_core::{HRESULT, HSTRING};
+
+    use super::*;
+    use crate::mock::MockObject;
+
+    thread_local! {
+        static LINES: Cell<[*mut c_void; 2]> = const { Cell::new([std::ptr::null_mut(); 2]) };
+    }
+
+    // get_Lines: hands out a CoTaskMem array owning one reference per line
+    unsafe extern "system" fn get_lines(_: *mut c_void, count: *mut u32, data: *mut *mut *mut c_void) -> HRESULT {
+        let lines = LINES.with(Cell::get);
+        unsafe {
+            let buffer = CoTaskMemAlloc(size_of_val(&lines)) as *mut *mut c_void;
+            for (i, line) in lines.iter().enumerate() {
+                let add_ref: unsafe extern "system" fn(*mut c_void) -> u32 =
+                    std::mem::transmute(*(*(*line as *const *const *const c_void)).add(1));
+                add_ref(*line);
+                *buffer.add(i) = *line;
+            }
+            *count = lines.len() as u32;
+            *data = buffer;
+        }
+        HRESULT(0)
+    }
+
+    unsafe extern "system" fn text_hello(_: *mut c_void, out: *mut *mut c_void) -> HRESULT {
+        unsafe { *out = std::mem::transmute::<HSTRING, *mut c_void>(HSTRING::from("hello")) };
+        HRESULT(0)
+    }
+
+    unsafe extern "system" fn text_world(_: *mut c_void, out: *mut *mut c_void) -> HRESULT {
+        unsafe { *out = std::mem::transmute::<HSTRING, *mut c_void>(HSTRING::from("world")) };
+        HRESULT(0)
+    }
+
+    #[test]
+    fn lines_reads_text_of_each_line() {
+        let first = MockObject::new(&[std::ptr::null(), text_hello as *const c_void]);
+        let second = MockObject::new(&[std::ptr::null(), text_world as *const c_void]);
+        LINES.with(|l| l.set([first.as_raw(), second.as_raw()]));
+        let result = MockObject::new(&[get_lines as *const c_void]);
+
+        {
+            let text = DynRecognizedText::new(&result.value()).unwrap();
+            assert_eq!(text.lines().unwrap(), vec!["hello".to_string(), "world".to_string()]);
+        }
+
+        // The received array and its copies released every line reference
+        assert_eq!((first.refs(), second.refs(), result.refs()), (0, 0, 0));
+    }
+
+    #[test]
+    fn slots_match_winmd_method_order() {
+        use windows_metadata::reader;
+
+        // Ships with Office; skip where it is not installed
+        let Some(index) = reader::Index::read(
+            r"C:\Program Files\Microsoft Office\root\vfs\ProgramFilesCommonX64\Microsoft Shared\Office16\AI\Microsoft.Windows.AI.Imaging.winmd",
+        ) else {
+            return;
+        };
+        let ns = "Microsoft.Windows.AI.Imaging";
+
+        let (owner, slot) = crate::meta::vtable_index_for(&index, ns, "IRecognizedText", "get_Lines").unwrap();
+        assert_eq!(owner, (ns.to_string(), "IRecognizedText".to_string()));
+        assert_eq!(slot, LINES_SLOT);
+
+        let (owner, slot) = crate::meta::vtable_index_for(&index, ns, "IRecognizedLine", "get_Text").unwrap();
+        assert_eq!(owner, (ns.to_string(), "IRecognizedLine".to_string()));
+        assert_eq!(slot, LINE_TEXT_SLOT);
+    }
+}
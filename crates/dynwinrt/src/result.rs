@@ -11,6 +11,32 @@ pub enum Error {
     TypeNotFound(String),
     NotAnInterface(String),
     MethodNotFound(String, String),
+    /// GetResults on an async operation whose results were already consumed
+    /// (`RO_E_CLOSED`), or a completed future polled again.
+    AsyncResultConsumed,
+    /// The async operation completed with `AsyncStatus::Error`; carries its `ErrorCode`.
+    AsyncFailed(windows_core::HRESULT),
+    /// The async operation completed with `AsyncStatus::Canceled`.
+    AsyncCanceled,
+    /// `WinRTValue::from_async`: the type is not an async type, or the
+    /// `IAsyncInfo` does not implement its interface; carries the type.
+    AsyncTypeMismatch(TypeKind),
+    /// Activation failed with `REGDB_E_CLASSNOTREG`; carries the class name.
+    ClassNotRegistered(String),
+    /// The class's activation factory does not implement the requested statics
+    /// interface; carries the class name and the interface IID.
+    InterfaceNotSupported(String, windows_core::GUID),
+    /// `cast_checked` with `require_agile`: the object behind this interface
+    /// does not implement `IAgileObject`.
+    NotAgile(windows_core::GUID),
+    /// A dynamic call got a different number of in arguments than its
+    /// signature has non-out parameters.
+    ArgCountMismatch { expected: usize, got: usize },
+    /// A `Unit`, `Async` or `OutValue` value was given where an in argument's
+    /// type is taken from the value; carries the value's type.
+    NotAnInParam(TypeKind),
+    /// A step in a call chain failed; carries the step index and the underlying error.
+    CallFailed(usize, Box<Error>),
 }
 
 impl Error {
@@ -41,10 +67,98 @@ impl Error {
             Error::MethodNotFound(iface, method) => {
                 format!("Method '{}' not found on interface '{}'", method, iface)
             }
+            Error::AsyncResultConsumed => {
+                "Async operation results were already consumed".to_string()
+            }
+            Error::AsyncFailed(hr) => format!("Async operation failed: 0x{:08X}", hr.0 as u32),
+            Error::AsyncCanceled => "Async operation was canceled".to_string(),
+            Error::AsyncTypeMismatch(kind) => {
+                format!("IAsyncInfo does not implement async type {:?}", kind)
+            }
+            Error::ClassNotRegistered(class) => format!(
+                "Class {} is not registered; is its package or the Windows App SDK runtime activated?",
+                class
+            ),
+            Error::InterfaceNotSupported(class, iid) => {
+                format!("Activation factory of {} does not implement {:?}", class, iid)
+            }
+            Error::NotAgile(iid) => format!("Object implementing {:?} is not agile", iid),
+            Error::ArgCountMismatch { expected, got } => {
+                format!("Expected {} in arguments, got {}", expected, got)
+            }
+            Error::NotAnInParam(kind) => {
+                format!("A {:?} value cannot be passed as an in argument", kind)
+            }
+            Error::CallFailed(index, inner) => {
+                format!("Call {} in chain failed: {}", index, inner.message())
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Index of the failing step for `CallFailed`, `None` otherwise.
+    pub fn failed_call_index(&self) -> Option<usize> {
+        match self {
+            Error::CallFailed(index, _) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Like `message`, but HRESULTs are spelled out with their facility and
+    /// the system description (`FormatMessageW`), e.g.
+    /// `0x80070057 (FACILITY_WIN32): The parameter is incorrect.`
+    /// An error's own message is appended when it says something more.
+    pub fn human_message(&self) -> String {
+        match self {
+            Error::WindowsError(err) => {
+                let described = describe_hresult(err.code());
+                let own = err.message();
+                if own.is_empty() || own == err.code().message() {
+                    described
+                } else {
+                    format!("{} ({})", described, own)
+                }
+            }
+            Error::AsyncFailed(hr) => format!("Async operation failed: {}", describe_hresult(*hr)),
+            Error::CallFailed(index, inner) => {
+                format!("Call {} in chain failed: {}", index, inner.human_message())
+            }
+            other => other.message(),
         }
     }
 }
 
+fn describe_hresult(hr: windows_core::HRESULT) -> String {
+    let description = hr.message();
+    let description = description.trim_end();
+    match facility_name(hr) {
+        Some(facility) => format!("0x{:08X} ({}): {}", hr.0 as u32, facility, description),
+        None => format!("0x{:08X}: {}", hr.0 as u32, description),
+    }
+}
+
+/// Name of the facility field (bits 16-28) of a failure HRESULT.
+fn facility_name(hr: windows_core::HRESULT) -> Option<&'static str> {
+    if hr.is_ok() {
+        return None;
+    }
+    Some(match (hr.0 as u32 >> 16) & 0x1FFF {
+        0 => "FACILITY_NULL",
+        1 => "FACILITY_RPC",
+        2 => "FACILITY_DISPATCH",
+        3 => "FACILITY_STORAGE",
+        4 => "FACILITY_ITF",
+        7 => "FACILITY_WIN32",
+        8 => "FACILITY_WINDOWS",
+        10 => "FACILITY_CONTROL",
+        11 => "FACILITY_CERT",
+        12 => "FACILITY_INTERNET",
+        25 => "FACILITY_HTTP",
+        _ => return None,
+    })
+}
+
 impl From<windows::core::Error> for Error {
     fn from(value: windows::core::Error) -> Self {
         Self::WindowsError(value)
@@ -52,3 +166,24 @@ impl From<windows::core::Error> for Error {
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_message_spells_out_hresults() {
+        let e_invalidarg = windows_core::HRESULT(0x80070057u32 as i32);
+        let message = Error::WindowsError(e_invalidarg.into()).human_message();
+        assert!(message.starts_with("0x80070057 (FACILITY_WIN32): "), "{message}");
+        assert!(message.len() > "0x80070057 (FACILITY_WIN32): ".len());
+        assert!(message.contains(e_invalidarg.message().trim_end()));
+
+        // A custom message is kept alongside the system description
+        let custom = Error::WindowsError(windows_core::Error::new(e_invalidarg, "bad slot"));
+        assert!(custom.human_message().ends_with("(bad slot)"));
+
+        let chained = Error::CallFailed(2, Box::new(Error::AsyncFailed(e_invalidarg)));
+        assert!(chained.human_message().starts_with("Call 2 in chain failed: Async operation failed: 0x80070057"));
+    }
+}
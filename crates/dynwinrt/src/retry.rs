@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use windows_core::HRESULT;
+
+use crate::result::{Error, Result};
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
+
+/// RPC_E_DISCONNECTED: the object invoked has disconnected from its clients.
+pub const RPC_E_DISCONNECTED: HRESULT = HRESULT(0x80010108u32 as i32);
+/// RPC_E_SERVER_UNAVAILABLE (HRESULT_FROM_WIN32(RPC_S_SERVER_UNAVAILABLE)).
+pub const RPC_E_SERVER_UNAVAILABLE: HRESULT = HRESULT(0x800706BAu32 as i32);
+
+/// When and how often `call_with_retry` re-issues a failed call.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one. Zero is treated as one.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Factor applied to the delay after each retry.
+    pub backoff_multiplier: u32,
+    /// HRESULTs considered transient; anything else fails immediately.
+    pub transient: Vec<HRESULT>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            backoff_multiplier: 2,
+            transient: vec![RPC_E_DISCONNECTED, RPC_E_SERVER_UNAVAILABLE],
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn is_transient(&self, err: &Error) -> bool {
+        match err {
+            Error::WindowsError(e) => self.transient.contains(&e.code()),
+            _ => false,
+        }
+    }
+}
+
+impl WinRTValue {
+    /// Like `call`, but retries on the policy's transient HRESULTs with
+    /// exponential backoff. Returns the last error once attempts run out.
+    pub fn call_with_retry(
+        &self,
+        method_index: usize,
+        params: &[ParamSpec],
+        args: &[WinRTValue],
+        policy: &RetryPolicy,
+    ) -> Result<Vec<WinRTValue>> {
+        let attempts = policy.max_attempts.max(1);
+        let mut delay = policy.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.call(method_index, params, args) {
+                Err(e) if attempt < attempts && policy.is_transient(&e) => {
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(policy.backoff_multiplier);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+
+    use super::*;
+    use crate::metadata_table::MetadataTable;
+    use crate::mock::MockObject;
+
+    thread_local! {
+        /// (calls so far, failures before success, failure HRESULT)
+        static FLAKY: Cell<(u32, u32, HRESULT)> = const { Cell::new((0, 0, HRESULT(0))) };
+    }
+
+    // Slot 6: fails `fail_times` times with `hr`, then writes 42 to its i32 out param
+    unsafe extern "system" fn flaky_get(_: *mut c_void, out: *mut i32) -> HRESULT {
+        let (calls, fail_times, hr) = FLAKY.with(Cell::get);
+        FLAKY.with(|f| f.set((calls + 1, fail_times, hr)));
+        if calls < fail_times {
+            return hr;
+        }
+        unsafe { *out = 42 };
+        HRESULT(0)
+    }
+
+    fn run(fail_times: u32, hr: HRESULT, policy: &RetryPolicy) -> (Result<Vec<WinRTValue>>, u32) {
+        FLAKY.with(|f| f.set((0, fail_times, hr)));
+        let mock = MockObject::new(&[flaky_get as *const c_void]);
+        let table = MetadataTable::new();
+        let result = mock.value().call_with_retry(6, &[ParamSpec::Out(table.i32_type())], &[], policy);
+        assert_eq!(mock.refs(), 0);
+        (result, FLAKY.with(Cell::get).0)
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy { max_attempts, initial_backoff: Duration::ZERO, ..RetryPolicy::default() }
+    }
+
+    #[test]
+    fn retries_transient_until_success() {
+        let (result, calls) = run(2, RPC_E_DISCONNECTED, &fast_policy(3));
+        assert_eq!(calls, 3);
+        assert!(matches!(result.unwrap()[..], [WinRTValue::I32(42)]));
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let (result, calls) = run(u32::MAX, RPC_E_SERVER_UNAVAILABLE, &fast_policy(4));
+        assert_eq!(calls, 4);
+        match result {
+            Err(Error::WindowsError(e)) => assert_eq!(e.code(), RPC_E_SERVER_UNAVAILABLE),
+            other => panic!("expected transient error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn non_transient_fails_immediately() {
+        let e_fail = HRESULT(0x80004005u32 as i32);
+        let (result, calls) = run(1, e_fail, &fast_policy(5));
+        assert_eq!(calls, 1);
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,49 @@
 use windows::Win32::System::WinRT::{IActivationFactory, RoGetActivationFactory};
 use windows::Win32::System::LibraryLoader::{LoadLibraryW, GetProcAddress};
-use windows_core::{HSTRING, HRESULT, IUnknown, Interface, PCSTR};
+use windows_core::{GUID, HSTRING, HRESULT, IUnknown, Interface, PCSTR};
 
 use crate::value::WinRTValue;
 
+// Raw binding so the factory can be requested on an arbitrary IID; the
+// windows crate wrapper is generic over a static interface type.
+mod raw {
+    windows_link::link!("combase.dll" "system" fn RoGetActivationFactory(
+        activatableclassid: *mut std::ffi::c_void,
+        iid: *const windows_core::GUID,
+        factory: *mut *mut std::ffi::c_void,
+    ) -> windows_core::HRESULT);
+    // The windows crate wrapper maps S_FALSE to Ok(()), hiding "already initialized".
+    windows_link::link!("combase.dll" "system" fn RoInitialize(inittype: i32) -> windows_core::HRESULT);
+}
+
+/// S_FALSE from RoInitialize: the thread was already initialized in this mode.
+const S_FALSE: HRESULT = HRESULT(1);
+/// RO_INIT_MULTITHREADED
+const RO_INIT_MULTITHREADED: i32 = 1;
+
+/// Initialize the Windows Runtime on this thread in the multithreaded apartment.
+/// `Ok(true)` if this call initialized it, `Ok(false)` if the thread already was
+/// in the MTA; either way the call should be balanced by `RoUninitialize`.
+/// Fails with `RPC_E_CHANGED_MODE` if the thread is already single-threaded,
+/// where dynamic calls that assume the MTA would misbehave.
+pub fn ensure_mta() -> crate::result::Result<bool> {
+    let hr = unsafe { raw::RoInitialize(RO_INIT_MULTITHREADED) };
+    hr.ok()?;
+    Ok(hr != S_FALSE)
+}
+
+/// REGDB_E_CLASSNOTREG: no activation factory is registered for the class.
+const REGDB_E_CLASSNOTREG: HRESULT = HRESULT(0x80040154u32 as i32);
+
+/// Map an activation failure, naming the class when it is not registered.
+pub(crate) fn activation_error(class_name: &HSTRING, e: windows_core::Error) -> crate::result::Error {
+    if e.code() == REGDB_E_CLASSNOTREG {
+        crate::result::Error::ClassNotRegistered(class_name.to_string())
+    } else {
+        crate::result::Error::WindowsError(e)
+    }
+}
+
 #[allow(dead_code)]
 pub fn ro_get_activation_factory(class_name: &HSTRING) -> windows_core::Result<IActivationFactory> {
     unsafe { RoGetActivationFactory::<IActivationFactory>(class_name) }
@@ -31,12 +71,59 @@ pub fn ro_get_activation_factory_2(class_name: &HSTRING) -> crate::result::Resul
                     std::mem::forget(factory);
                     Ok(WinRTValue::Object(ukn))
                 }
-                Err(e) => Err(crate::result::Error::WindowsError(e)),
+                Err(e) => Err(activation_error(class_name, e)),
             }
         }
     }
 }
 
+/// Get the activation factory for `class_name` directly on interface `iid`
+/// (e.g. a statics or factory interface), skipping the IActivationFactory + QI round trip.
+pub fn ro_get_activation_factory_for(class_name: &HSTRING, iid: &GUID) -> crate::result::Result<WinRTValue> {
+    let class_id: *mut std::ffi::c_void = unsafe { std::mem::transmute_copy(class_name) };
+    let mut factory_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+    unsafe { raw::RoGetActivationFactory(class_id, iid, &mut factory_ptr) }
+        .ok()
+        .map_err(|e| activation_error(class_name, e))?;
+    if factory_ptr.is_null() {
+        return Ok(WinRTValue::Null);
+    }
+    Ok(WinRTValue::Object(unsafe { IUnknown::from_raw(factory_ptr) }))
+}
+
+/// Full names of runtime classes in `namespace` that carry `[activatable]`,
+/// i.e. that can be created through `ActivateInstance` or a factory interface.
+/// Sorted and deduplicated across the winmd files in `index`.
+pub fn activatable_classes(index: &windows_metadata::reader::Index, namespace: &str) -> Vec<String> {
+    activatable_classes_where(index, |ns| ns == namespace)
+}
+
+/// Like `activatable_classes`, but for `namespace_prefix` and every namespace
+/// nested under it (`Windows.Foundation` also covers
+/// `Windows.Foundation.Collections`, not `Windows.FoundationX`).
+pub fn list_activatable_classes(index: &windows_metadata::reader::Index, namespace_prefix: &str) -> Vec<String> {
+    activatable_classes_where(index, |ns| {
+        ns.strip_prefix(namespace_prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+fn activatable_classes_where(
+    index: &windows_metadata::reader::Index,
+    namespace_matches: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    use windows_metadata::HasAttributes;
+
+    let mut classes: Vec<String> = index.all()
+        .filter(|def| namespace_matches(def.namespace()))
+        .filter(|def| def.extends().is_some_and(|e| e.namespace() == "System" && e.name() == "Object"))
+        .filter(|def| def.has_attribute("ActivatableAttribute"))
+        .map(|def| format!("{}.{}", def.namespace(), def.name()))
+        .collect();
+    classes.sort();
+    classes.dedup();
+    classes
+}
+
 /// C++/WinRT-style fallback: probe DLLs by trimming the class name at each '.'
 /// and calling DllGetActivationFactory. This enables regfree WinRT activation
 /// for WinAppSDK classes whose factories aren't in the COM registry.
@@ -115,6 +202,58 @@ mod tests {
     use super::*;
 
     #[test]
+    fn ensure_mta_reports_already_initialized() {
+        std::thread::spawn(|| {
+            assert!(ensure_mta().unwrap());
+            assert!(!ensure_mta().unwrap());
+            unsafe {
+                windows::Win32::System::WinRT::RoUninitialize();
+                windows::Win32::System::WinRT::RoUninitialize();
+            }
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn ensure_mta_fails_on_single_threaded_apartment() {
+        use windows::Win32::System::WinRT::{RO_INIT_SINGLETHREADED, RoUninitialize};
+
+        std::thread::spawn(|| {
+            unsafe { RoInitialize(RO_INIT_SINGLETHREADED) }.unwrap();
+            match ensure_mta() {
+                Err(crate::result::Error::WindowsError(e)) => {
+                    assert_eq!(e.code(), HRESULT(0x80010106u32 as i32)) // RPC_E_CHANGED_MODE
+                }
+                other => panic!("expected RPC_E_CHANGED_MODE, got {other:?}"),
+            }
+            unsafe { RoUninitialize() };
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn activatable_classes_in_foundation() {
+        let index = windows_metadata::reader::Index::read(
+            r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd",
+        )
+        .unwrap();
+        let classes = activatable_classes(&index, "Windows.Foundation");
+        assert!(classes.iter().any(|c| c == "Windows.Foundation.Uri"));
+        // Static-only classes are not activatable
+        assert!(!classes.iter().any(|c| c == "Windows.Foundation.PropertyValue"));
+        assert!(classes.windows(2).all(|w| w[0] < w[1]));
+
+        // A prefix also covers nested namespaces, but only at a '.' boundary
+        let nested = list_activatable_classes(&index, "Windows.Foundation");
+        assert!(nested.iter().any(|c| c == "Windows.Foundation.Uri"));
+        assert!(nested.iter().any(|c| c == "Windows.Foundation.Collections.PropertySet"));
+        assert!(list_activatable_classes(&index, "Windows.Found").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
     fn call_get_activation_factory() -> windows::core::Result<()> {
         // Ignore error if already initialized
         let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
@@ -149,4 +288,53 @@ mod tests {
         println!("Got activation factory {:?} {:?}", inspect, activateFactory);
         Ok(())
     }
+
+    #[test]
+    fn unregistered_class_names_the_class() {
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+        let name = h!("Dynwinrt.Bogus.NoSuchClass");
+
+        match ro_get_activation_factory_2(name) {
+            Err(crate::result::Error::ClassNotRegistered(class)) => assert_eq!(class, "Dynwinrt.Bogus.NoSuchClass"),
+            other => panic!("expected ClassNotRegistered, got {:?}", other.map(|_| ())),
+        }
+        assert!(matches!(
+            ro_get_activation_factory_for(name, &IUriRuntimeClassFactory::IID),
+            Err(crate::result::Error::ClassNotRegistered(_))
+        ));
+        assert!(matches!(
+            WinRTValue::from_activation_factory(name),
+            Err(crate::result::Error::ClassNotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn get_activation_factory_for_iid() -> crate::result::Result<()> {
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let factory = ro_get_activation_factory_for(
+            h!("Windows.Foundation.Uri"),
+            &IUriRuntimeClassFactory::IID,
+        )?;
+
+        // Already on IUriRuntimeClassFactory: CreateUri is at vtable index 6, no QI needed
+        let reg = crate::metadata_table::MetadataTable::new();
+        let mut sig = crate::signature::InterfaceSignature::define_from_iinspectable(
+            "IUriRuntimeClassFactory",
+            IUriRuntimeClassFactory::IID,
+            &reg,
+        );
+        sig.add_method(
+            crate::signature::MethodSignature::new(&reg)
+                .add_in(reg.hstring())
+                .add_out(reg.object()),
+        );
+        let result = sig.methods[6].call_dynamic(
+            factory.as_object().unwrap().as_raw(),
+            &[WinRTValue::HString(h!("https://www.example.com/path").clone())],
+        )?;
+        let uri: Uri = result[0].as_object().unwrap().cast()?;
+        assert_eq!(uri.Path()?, "/path");
+        Ok(())
+    }
 }
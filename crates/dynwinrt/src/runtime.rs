@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use windows_core::{GUID, HSTRING};
+use windows_metadata::{HasAttributes, ParamAttributes, Type, Value, reader};
+
+use crate::metadata_table::MetadataTable;
+use crate::result::{Error, Result};
+use crate::roapi::ro_get_activation_factory_for;
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
+
+/// Calls WinRT methods by class and method name, with vtable slots and
+/// parameter types read from winmd instead of hand-written signatures.
+///
+/// Instance methods are looked up on the interfaces the class implements and
+/// take the instance as `args[0]`; statics and factory methods are looked up
+/// on the class's `[static]` and `[activatable]` interfaces and called on the
+/// activation factory. Overloads are told apart by their in-parameter count;
+/// when an instance and a static method both fit, it is an instance call only
+/// if `args[0]` implements the instance method's interface.
+pub struct DynRuntime {
+    index: reader::Index,
+    table: Arc<MetadataTable>,
+}
+
+/// A method resolved from winmd: the interface to call it on, its vtable
+/// slot, and its ABI parameters in declaration order.
+struct ResolvedMethod {
+    iid: GUID,
+    slot: usize,
+    params: Vec<ParamSpec>,
+    is_static: bool,
+}
+
+impl DynRuntime {
+    /// Load and index the given winmd files.
+    pub fn new<P: AsRef<str>>(winmd_paths: &[P]) -> Result<Self> {
+        let files = winmd_paths
+            .iter()
+            .map(|p| {
+                reader::File::read(p.as_ref())
+                    .ok_or_else(|| Error::TypeNotFound(format!("winmd {}", p.as_ref())))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(DynRuntime { index: reader::Index::new(files), table: MetadataTable::new() })
+    }
+
+    pub fn table(&self) -> &Arc<MetadataTable> {
+        &self.table
+    }
+
+    /// Call `method` on runtime class `class` (full name, e.g.
+    /// `Windows.Foundation.Uri`). Returns the out values, with the metadata
+    /// return value last.
+    pub fn call(&self, class: &str, method: &str, args: &[WinRTValue]) -> Result<Vec<WinRTValue>> {
+        let (namespace, name) = class
+            .rsplit_once('.')
+            .ok_or_else(|| Error::TypeNotFound(class.to_string()))?;
+        let def = self.index.get(namespace, name).next()
+            .ok_or_else(|| Error::TypeNotFound(class.to_string()))?;
+
+        let instance_ifaces: Vec<Type> = def.interface_impls().map(|i| i.interface(&[])).collect();
+        let instance = match args.split_first() {
+            Some((_, rest)) => self.find_method(&instance_ifaces, method, rest.len(), false)?,
+            None => None,
+        };
+        let statics = self.find_method(&factory_interfaces(&def), method, args.len(), true)?;
+        let m = match (instance, statics) {
+            (Some(i), Some(s)) => if args[0].cast(&i.iid).is_ok() { i } else { s },
+            (Some(m), None) | (None, Some(m)) => m,
+            (None, None) => {
+                // An instance method called without its instance
+                if args.is_empty() && self.find_method(&instance_ifaces, method, 0, false)?.is_some() {
+                    return Err(Error::ArgCountMismatch { expected: 1, got: 0 });
+                }
+                return Err(Error::MethodNotFound(class.to_string(), method.to_string()));
+            }
+        };
+
+        if m.is_static {
+            let factory = ro_get_activation_factory_for(&HSTRING::from(class), &m.iid)?;
+            factory.call(m.slot, &m.params, args)
+        } else {
+            let instance = args[0].cast(&m.iid)?;
+            instance.call(m.slot, &m.params, &args[1..])
+        }
+    }
+
+    /// First method named `method` with `in_count` in params on any of the
+    /// non-generic `interfaces`.
+    fn find_method(
+        &self,
+        interfaces: &[Type],
+        method: &str,
+        in_count: usize,
+        is_static: bool,
+    ) -> Result<Option<ResolvedMethod>> {
+        for iface in interfaces {
+            let Type::Name(tn) = iface else { continue };
+            if !tn.generics.is_empty() {
+                continue;
+            }
+            let Some(def) = self.index.get(&tn.namespace, &tn.name).next() else { continue };
+            for (i, md) in def.methods().enumerate() {
+                if md.name() != method {
+                    continue;
+                }
+                let params = self.param_specs(&md)?;
+                if params.iter().filter(|p| matches!(p, ParamSpec::In(_))).count() != in_count {
+                    continue;
+                }
+                let iid = self.table.type_from_metadata(&self.index, iface, &[])?
+                    .iid()
+                    .ok_or_else(|| Error::NotAnInterface(format!("{}.{}", tn.namespace, tn.name)))?;
+                return Ok(Some(ResolvedMethod { iid, slot: 6 + i, params, is_static }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// ABI parameters of `md`: declared params in order, then the return value.
+    fn param_specs(&self, md: &reader::MethodDef) -> Result<Vec<ParamSpec>> {
+        let sig = md.signature(&[]);
+        let flags: Vec<ParamAttributes> = md.params()
+            .filter(|p| p.sequence() > 0)
+            .map(|p| p.flags())
+            .collect();
+        let mut specs = Vec::with_capacity(sig.types.len() + 1);
+        for (i, ty) in sig.types.iter().enumerate() {
+            let is_out = flags.get(i).is_some_and(|f| f.contains(ParamAttributes::Out));
+            specs.push(match (ty, is_out) {
+                (Type::RefMut(inner), true) => ParamSpec::Out(self.metadata_type(inner)?),
+                (Type::Array(_), true) => ParamSpec::OutFill(self.metadata_type(ty)?),
+                (_, true) => ParamSpec::Out(self.metadata_type(ty)?),
+                (_, false) => ParamSpec::In(self.metadata_type(ty)?),
+            });
+        }
+        if sig.return_type != Type::Void {
+            specs.push(ParamSpec::Out(self.metadata_type(&sig.return_type)?));
+        }
+        Ok(specs)
+    }
+
+    fn metadata_type(&self, ty: &Type) -> Result<crate::metadata_table::TypeHandle> {
+        self.table.type_from_metadata(&self.index, ty, &[])
+    }
+}
+
+/// Interfaces named by `[static(...)]` and `[activatable(...)]` on a class.
+fn factory_interfaces(def: &reader::TypeDef) -> Vec<Type> {
+    def.attributes()
+        .filter(|a| matches!(a.name(), "StaticAttribute" | "ActivatableAttribute"))
+        .filter_map(|a| {
+            a.value().into_iter().find_map(|(_, v)| match v {
+                Value::TypeName(tn) => Some(Type::Name(tn)),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use windows_core::h;
+
+    use super::*;
+
+    const WINDOWS_WINMD: &str =
+        r"C:\Program Files (x86)\Windows Kits\10\UnionMetadata\10.0.26100.0\Windows.winmd";
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn call_uri_scheme_name_by_name() {
+        let rt = DynRuntime::new(&[WINDOWS_WINMD]).unwrap();
+
+        // IUriRuntimeClassFactory::CreateUri, found through [activatable]
+        let uri = rt
+            .call("Windows.Foundation.Uri", "CreateUri", &[WinRTValue::HString(h!("https://www.example.com").clone())])
+            .unwrap()
+            .remove(0);
+        let scheme = rt.call("Windows.Foundation.Uri", "get_SchemeName", &[uri]).unwrap();
+        assert_eq!(scheme, vec![WinRTValue::HString(h!("https").clone())]);
+
+        assert!(matches!(
+            rt.call("Windows.Foundation.Uri", "get_SchemeName", &[]),
+            Err(Error::ArgCountMismatch { expected: 1, got: 0 })
+        ));
+        assert!(matches!(
+            rt.call("Windows.Foundation.Uri", "NoSuchMethod", &[]),
+            Err(Error::MethodNotFound(..))
+        ));
+    }
+}
